@@ -0,0 +1,55 @@
+# Check that every `#[serde(rename = "...")]` of `src/schema.rs` matches a property name or an enum value of the
+# vega-lite json schema, so that a typo in a rename (that would silently produce an invalid spec) is caught.
+#
+# usage: python3 scripts/check_renames.py [schema.rs] [url or path of the json schema]
+import json
+import re
+import sys
+import urllib.request
+
+rust_file = sys.argv[1] if len(sys.argv) > 1 else "src/schema.rs"
+schema_src = sys.argv[2] if len(sys.argv) > 2 else "https://vega.github.io/schema/vega-lite/v4.0.2.json"
+
+if re.match(r"^https?://", schema_src):
+    with urllib.request.urlopen(schema_src) as r:
+        schema = json.load(r)
+else:
+    with open(schema_src, 'r', encoding="utf-8") as f:
+        schema = json.load(f)
+
+known = set()
+
+
+def collect(node):
+    if isinstance(node, dict):
+        for (key, value) in node.items():
+            if key == "properties" and isinstance(value, dict):
+                known.update(value.keys())
+            elif key == "enum" and isinstance(value, list):
+                known.update(v for v in value if isinstance(v, str))
+            elif key == "const" and isinstance(value, str):
+                known.add(value)
+            collect(value)
+    elif isinstance(node, list):
+        for value in node:
+            collect(value)
+
+
+collect(schema)
+
+unknown = []
+current = None
+with open(rust_file, 'r', encoding="utf-8", errors='replace') as f:
+    for (number, line) in enumerate(f, start=1):
+        m = re.search(r"pub (struct|enum) (\w+)", line)
+        if m:
+            current = m.group(2)
+        m = re.search(r'#\[serde\(rename = "([^"]*)"\)\]', line)
+        if m and m.group(1) not in known:
+            unknown.append("{}:{}: `{}` in {} is not in the json schema".format(rust_file, number, m.group(1), current))
+
+for u in unknown:
+    print(u)
+if unknown:
+    sys.exit(1)
+print("all renames of {} are in the json schema".format(rust_file))
@@ -95,3 +95,6 @@ echo '-- Fix doc links'
 sed -i 's/types#datetime/struct.DateTime.html/' $file
 
 cargo fmt -- $file
+
+echo '-- check renames against the json schema'
+python3 scripts/check_renames.py $file $url
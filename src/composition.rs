@@ -0,0 +1,193 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+/// How a chart is composed, see [`Vegalite::composition_kind`](struct.Vegalite.html#method.composition_kind)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositionKind {
+    /// A single view, with a `mark`
+    Unit,
+    /// Views layered on top of each other, with `layer`
+    Layer,
+    /// A view faceted by a field, with `facet` and `spec`
+    Facet,
+    /// A view repeated for several fields, with `repeat` and `spec`
+    Repeat,
+    /// Views put side by side, with `concat`, `hconcat` or `vconcat`
+    Concat,
+}
+
+impl Field {
+    /// The field being repeated, serialized as `{"repeat": "repeat"}`, to use in the encoding of a repeated spec.
+    pub fn repeated() -> Self {
+        Field::RepeatRef(RepeatRef {
+            repeat: Some(RepeatEnum::Repeat),
+        })
+    }
+}
+
+impl Vegalite {
+    /// Find how the chart is composed from which fields are set. A chart without any composition field is
+    /// considered a unit chart.
+    pub fn composition_kind(&self) -> CompositionKind {
+        if self.facet.is_some() {
+            CompositionKind::Facet
+        } else if self.repeat.is_some() {
+            CompositionKind::Repeat
+        } else if self.concat.is_some() || self.hconcat.is_some() || self.vconcat.is_some() {
+            CompositionKind::Concat
+        } else if self.layer.is_some() {
+            CompositionKind::Layer
+        } else {
+            CompositionKind::Unit
+        }
+    }
+
+    /// Is this a single view chart
+    pub fn is_unit(&self) -> bool {
+        self.composition_kind() == CompositionKind::Unit
+    }
+
+    /// Is this a layered chart
+    pub fn is_layered(&self) -> bool {
+        self.composition_kind() == CompositionKind::Layer
+    }
+
+    /// Is this a faceted chart
+    pub fn is_faceted(&self) -> bool {
+        self.composition_kind() == CompositionKind::Facet
+    }
+
+    /// Is this a concatenation of charts, horizontal, vertical or wrapped
+    pub fn is_concat(&self) -> bool {
+        self.composition_kind() == CompositionKind::Concat
+    }
+
+    /// Is this a repeated chart
+    pub fn is_repeat(&self) -> bool {
+        self.composition_kind() == CompositionKind::Repeat
+    }
+
+    /// Move the view of the chart (mark, encoding, layers, selection, size and projection) into a spec that can be
+    /// repeated or faceted. Data, transforms, title and config stay on the chart.
+    fn take_view_spec(&mut self) -> SpecClass {
+        SpecClass {
+            mark: self.mark.take(),
+            encoding: self.encoding.take(),
+            layer: self.layer.take(),
+            selection: self.selection.take(),
+            width: self.width.take(),
+            height: self.height.take(),
+            projection: self.projection.take(),
+            ..Default::default()
+        }
+    }
+
+    /// Facet the chart by `field`, wrapping the facets in `columns` columns. The current view of the chart (mark,
+    /// encoding, layers, selection, size and projection) is moved into the faceted `spec`.
+    pub fn facet_wrap(mut self, field: &str, ty: StandardType, columns: usize) -> Self {
+        let spec = self.take_view_spec();
+        self.spec = Some(spec);
+        self.facet = Some(Facet {
+            field: Some(field.into()),
+            facet_type: Some(ty),
+            ..Default::default()
+        });
+        self.columns = Some(columns as f64);
+        self
+    }
+
+    /// Repeat the layered view `layers` once for each of `fields`, for example a scatter plot with its regression
+    /// line for each variable. In the encodings of the layers, the repeated field is
+    /// [`Field::repeated`](enum.Field.html#method.repeated). Data, transforms, title and config stay on the
+    /// chart and are shared by every repetition. The current view of the chart is replaced by `layers`, its
+    /// selection, size and projection being moved into the repeated `spec`.
+    pub fn repeat_layer(mut self, fields: &[&str], layers: Vec<LayerSpec>) -> Self {
+        self.repeat = Some(RepeatUnion::StringArray(
+            fields.iter().map(|f| f.to_string()).collect(),
+        ));
+        let mut spec = self.take_view_spec();
+        spec.mark = None;
+        spec.encoding = None;
+        spec.layer = Some(layers);
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Mutable access to the scale, axis and legend resolutions of a composed chart, creating an empty one if
+    /// missing.
+    pub fn resolve_mut(&mut self) -> &mut Resolve {
+        self.resolve.get_or_insert_with(Default::default)
+    }
+
+    /// Give each facet its own y scale, serialized as `"resolve": {"scale": {"y": "independent"}}`, for small
+    /// multiples whose values differ too much to share an axis. Works for charts faceted with
+    /// [`facet_wrap`](#method.facet_wrap) as well as with the `row` and `column` channels.
+    pub fn facet_independent_y(mut self) -> Self {
+        self.resolve_mut()
+            .scale
+            .get_or_insert_with(Default::default)
+            .y = Some(ResolveMode::Independent);
+        self
+    }
+
+    /// Set the spacing in pixels between the sub-views of a composed chart.
+    pub fn set_spacing(&mut self, spacing: impl Into<Spacing>) -> &mut Self {
+        self.spacing = Some(spacing.into());
+        self
+    }
+
+    /// Set the alignment of the grid rows and columns of a composed chart.
+    pub fn set_align(&mut self, align: impl Into<AlignUnion>) -> &mut Self {
+        self.align = Some(align.into());
+        self
+    }
+
+    /// Set whether the sub-views of a composed chart are centered relative to their respective rows or columns.
+    pub fn set_center(&mut self, center: impl Into<VegaliteCenter>) -> &mut Self {
+        self.center = Some(center.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn repeat_layer_replaces_the_view() {
+        let mut chart = Vegalite {
+            mark: Some(Mark::Bar.into()),
+            width: Some(HeightUnion::Double(200.0)),
+            ..Default::default()
+        };
+        chart.encoding_mut().x = Some(XClassBuilder::default().field("a").build().unwrap());
+        let mut point = LayerSpec {
+            mark: Some(Mark::Point.into()),
+            ..Default::default()
+        };
+        point.encoding_mut().x = Some(XClass {
+            field: Some(Field::repeated()),
+            ..Default::default()
+        });
+        let chart = chart.repeat_layer(&["a", "b"], vec![point]);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("mark").is_none());
+        assert!(json.get("encoding").is_none());
+        assert!(json.get("width").is_none());
+        assert!(json["spec"].get("mark").is_none());
+        assert!(json["spec"].get("encoding").is_none());
+        assert_eq!(json["spec"]["width"], 200.0);
+        assert_eq!(json["spec"]["layer"][0]["mark"], "point");
+        assert_eq!(json["repeat"], serde_json::json!(["a", "b"]));
+    }
+}
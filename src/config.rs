@@ -0,0 +1,307 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::merge::Merge;
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Style of the titles, as set in the config
+pub type TitleConfig = ExcludeMappedValueRefBaseTitle;
+/// Builder for [`TitleConfig`](type.TitleConfig.html)
+pub type TitleConfigBuilder = ExcludeMappedValueRefBaseTitleBuilder;
+
+impl Config {
+    /// Load a config from json, for example a house theme.
+    pub fn from_json_str(json: &str) -> Result<Config, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Load a config from a json file, for example a house theme. A file that can't be read is reported as an
+    /// io error (see `serde_json::Error::is_io`).
+    pub fn from_json_path(path: impl AsRef<Path>) -> Result<Config, serde_json::Error> {
+        let file = File::open(path).map_err(serde_json::Error::io)?;
+        serde_json::from_reader(BufReader::new(file))
+    }
+
+    /// Remove the border drawn around the plot, serialized as `"view": {"stroke": null}`.
+    pub fn no_view_border(mut self) -> Self {
+        self.view.get_or_insert_with(Default::default).stroke = RemovableValue::Remove;
+        self
+    }
+
+    /// Set the default format of numbers in guides and text marks, as a
+    /// [d3-format](https://github.com/d3/d3-format#locale_format) specifier (for example `".2f"`).
+    pub fn number_format(mut self, format: &str) -> Self {
+        self.number_format = Some(format.to_string());
+        self
+    }
+
+    /// Set the default format of dates in guides and text marks, as a
+    /// [d3-time-format](https://github.com/d3/d3-time-format#locale_format) specifier (for example `"%Y-%m-%d"`).
+    pub fn time_format(mut self, format: &str) -> Self {
+        self.time_format = Some(format.to_string());
+        self
+    }
+
+    /// Set the style of all facet headers (row, column and wrapped facet), e.g. their `title_font_size` or
+    /// `label_angle`.
+    pub fn header(mut self, header: HeaderConfig) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Set the default layout (`columns` and `spacing`) of concatenated views.
+    pub fn concat(mut self, concat: CompositionConfig) -> Self {
+        self.concat = Some(concat);
+        self
+    }
+
+    /// Set the default spacing between concatenated views, in pixels.
+    pub fn concat_spacing(mut self, spacing: f64) -> Self {
+        self.concat.get_or_insert_with(Default::default).spacing = Some(spacing);
+        self
+    }
+
+    /// Set the default layout (`columns` and `spacing`) of facets.
+    pub fn facet(mut self, facet: CompositionConfig) -> Self {
+        self.facet = Some(facet);
+        self
+    }
+
+    /// Set the default spacing between facets, in pixels.
+    pub fn facet_spacing(mut self, spacing: f64) -> Self {
+        self.facet.get_or_insert_with(Default::default).spacing = Some(spacing);
+        self
+    }
+
+    /// Hide every axis: no domain line, ticks, labels, grid nor title, and no space reserved for them. Use
+    /// [`Vegalite::no_legends`](struct.Vegalite.html#method.no_legends) to hide legends, as they can't be disabled
+    /// from the config in Vega-Lite 4.0.
+    pub fn no_axes(mut self) -> Self {
+        let axis = self.axis.get_or_insert_with(Default::default);
+        axis.domain = Some(false);
+        axis.grid = Some(false);
+        axis.labels = Some(false);
+        axis.ticks = Some(false);
+        axis.title = RemovableValue::Remove;
+        axis.min_extent = Some(0.0);
+        axis.max_extent = Some(0.0);
+        self
+    }
+
+    /// Show or hide the grid lines of every axis.
+    pub fn axis_grid(mut self, grid: bool) -> Self {
+        self.axis.get_or_insert_with(Default::default).grid = Some(grid);
+        self
+    }
+
+    /// Show or hide the domain line of every axis.
+    pub fn axis_domain(mut self, domain: bool) -> Self {
+        self.axis.get_or_insert_with(Default::default).domain = Some(domain);
+        self
+    }
+
+    /// Show or hide the ticks of every axis.
+    pub fn axis_ticks(mut self, ticks: bool) -> Self {
+        self.axis.get_or_insert_with(Default::default).ticks = Some(ticks);
+        self
+    }
+
+    /// Show or hide the labels of every axis.
+    pub fn axis_labels(mut self, labels: bool) -> Self {
+        self.axis.get_or_insert_with(Default::default).labels = Some(labels);
+        self
+    }
+
+    /// Set the default properties of all legends (placement, layout, symbols, labels, ...). Legends can't be
+    /// disabled from the config in Vega-Lite 4.0, use [`Vegalite::no_legends`](struct.Vegalite.html#method.no_legends)
+    /// instead.
+    pub fn legend(mut self, legend: LegendConfig) -> Self {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Set where all legends are placed, e.g. `LegendOrient::Bottom` to move them under the charts.
+    pub fn legend_orient(mut self, orient: LegendOrient) -> Self {
+        self.legend.get_or_insert_with(Default::default).orient = Some(orient);
+        self
+    }
+
+    /// Set the number of columns the entries of symbol legends are laid out in.
+    pub fn legend_columns(mut self, columns: usize) -> Self {
+        self.legend.get_or_insert_with(Default::default).columns = Some(columns as f64);
+        self
+    }
+
+    /// Set the default padding around the visualization, in pixels, either the same on every side (`5.0.into()`)
+    /// or side by side with a `PaddingClass`.
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = Some(padding.into());
+        self
+    }
+
+    /// Set how the size of the visualization is determined by default, either with an `AutosizeType` (for
+    /// example `AutosizeType::Fit` for responsive embeds) or with `AutoSizeParams`.
+    pub fn autosize(mut self, autosize: impl Into<Autosize>) -> Self {
+        self.autosize = Some(autosize.into());
+        self
+    }
+
+    /// Set the style of all titles (font, size, anchor, color, ...).
+    pub fn title_style(mut self, title: TitleConfig) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    /// Set the anchor of all titles, e.g. `TitleAnchorEnum::Start` for left-aligned titles.
+    pub fn title_anchor(mut self, anchor: TitleAnchorEnum) -> Self {
+        self.title.get_or_insert_with(Default::default).anchor = Some(anchor);
+        self
+    }
+
+    /// Enable or disable tooltips on all marks. When enabled, a tooltip shows the fields of the encoding of the
+    /// hovered mark.
+    pub fn mark_tooltip(mut self, tooltip: bool) -> Self {
+        self.mark.get_or_insert_with(Default::default).tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Set the default properties of all bars, e.g. their `corner_radius` or `bin_spacing`, with a
+    /// `RectConfigBuilder`.
+    pub fn bar(mut self, bar: RectConfig) -> Self {
+        self.bar = Some(bar);
+        self
+    }
+
+    /// Set the default properties of all lines, e.g. their `point` overlay or `interpolate`, with a
+    /// `LineConfigBuilder`.
+    pub fn line(mut self, line: LineConfig) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Set the default properties of all point marks, with a `MarkConfigBuilder`.
+    pub fn point(mut self, point: MarkConfig) -> Self {
+        self.point = Some(point);
+        self
+    }
+
+    /// Set the default properties of all areas, e.g. their `line` or `point` overlays, with an
+    /// `AreaConfigBuilder`.
+    pub fn area(mut self, area: AreaConfig) -> Self {
+        self.area = Some(area);
+        self
+    }
+
+    /// Set the default properties of all ticks, e.g. their `thickness` or `band_size`, with a `TickConfigBuilder`.
+    pub fn tick(mut self, tick: TickConfig) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    /// Define a named style, that marks refer to by name (see [`AnyMark::style`](enum.AnyMark.html#method.style)),
+    /// to share mark properties across views and layers. A style with the same name is replaced.
+    pub fn add_style(mut self, name: &str, style: BaseMarkConfig) -> Self {
+        self.style
+            .get_or_insert_with(Default::default)
+            .insert(name.to_string(), style);
+        self
+    }
+}
+
+impl Vegalite {
+    /// Load a config from a json file (see [`Config::from_json_path`](struct.Config.html#method.from_json_path))
+    /// and merge it on top of the config of the chart: the fields set in the file replace the ones of the chart,
+    /// the others are kept.
+    pub fn apply_config_file(mut self, path: impl AsRef<Path>) -> Result<Self, serde_json::Error> {
+        let config = Config::from_json_path(path)?;
+        self.config.merge(&Some(config))?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn no_view_border() {
+        let json = serde_json::to_value(Config::default().no_view_border()).unwrap();
+        assert_eq!(json, json!({"view": {"stroke": null}}));
+    }
+
+    // a config file in the temp dir, removed when dropped
+    struct ConfigFile(std::path::PathBuf);
+
+    impl ConfigFile {
+        fn new(name: &str, json: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "vega_lite_4-{}-{}.json",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, json).unwrap();
+            ConfigFile(path)
+        }
+    }
+
+    impl Drop for ConfigFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn apply_config_file() {
+        test_charts::on_large_stack(|| {
+            let file = ConfigFile::new(
+                "theme",
+                r#"{"background": "grey", "view": {"fill": "white"}}"#,
+            );
+            let chart = Vegalite {
+                config: Some(Config {
+                    background: Some("white".to_string()),
+                    number_format: Some(".2f".to_string()),
+                    ..Config::default().no_view_border()
+                }),
+                ..Default::default()
+            };
+            let chart = chart.apply_config_file(&file.0).unwrap();
+            assert_eq!(
+                serde_json::to_value(&chart).unwrap()["config"],
+                json!({"background": "grey", "numberFormat": ".2f", "view": {"stroke": null, "fill": "white"}})
+            );
+        });
+    }
+
+    #[test]
+    fn missing_config_file() {
+        let path = std::env::temp_dir().join("vega_lite_4-missing-config.json");
+        let err = Config::from_json_path(path).unwrap_err();
+        assert!(err.is_io());
+    }
+
+    #[test]
+    fn bad_config_file() {
+        test_charts::on_large_stack(|| {
+            let file = ConfigFile::new("bad", r#"{"background": "grey""#);
+            let err = Vegalite::default().apply_config_file(&file.0).unwrap_err();
+            assert!(err.is_eof());
+            let file = ConfigFile::new("wrong-type", r#"{"background": 1}"#);
+            let err = Config::from_json_path(&file.0).unwrap_err();
+            assert!(err.is_data());
+        });
+    }
+}
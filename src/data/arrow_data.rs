@@ -0,0 +1,219 @@
+use crate::UrlData;
+use crate::UrlDataBuilder;
+use crate::UrlDataInlineDataset;
+use arrow::array::*;
+use arrow::datatypes::*;
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use serde_json::{Map, Number, Value};
+
+fn float_value(v: f64) -> Value {
+    // NaN and infinities have no json representation
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+// the types written with the arrow display: decimals (parsed back into numbers), temporal types (as ISO 8601
+// strings), dictionaries, binaries, ...
+fn is_formatted(data_type: &DataType) -> bool {
+    !matches!(
+        data_type,
+        DataType::Null
+            | DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::List(_)
+            | DataType::LargeList(_)
+            | DataType::FixedSizeList(_, _)
+            | DataType::Struct(_)
+    )
+}
+
+// a column, with what's needed to convert its values built once for all the rows
+struct Column<'a> {
+    array: &'a dyn Array,
+    // the display of the formatted types
+    formatter: Option<ArrayFormatter<'a>>,
+    // the fields of a struct
+    children: Vec<Column<'a>>,
+}
+
+impl<'a> Column<'a> {
+    fn new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        let formatter = if is_formatted(array.data_type()) {
+            Some(ArrayFormatter::try_new(array, &FormatOptions::default())?)
+        } else {
+            None
+        };
+        let children = match array.data_type() {
+            DataType::Struct(_) => array
+                .as_struct()
+                .columns()
+                .iter()
+                .map(|column| Column::new(column.as_ref()))
+                .collect::<Result<_, _>>()?,
+            _ => Vec::new(),
+        };
+        Ok(Column {
+            array,
+            formatter,
+            children,
+        })
+    }
+
+    fn value(&self, i: usize) -> Result<Value, ArrowError> {
+        let array = self.array;
+        if array.is_null(i) {
+            return Ok(Value::Null);
+        }
+        if let Some(formatter) = &self.formatter {
+            let formatted = formatter.value(i).to_string();
+            return Ok(match array.data_type() {
+                DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => {
+                    let v = formatted.parse::<f64>().map_err(|e| {
+                        ArrowError::ParseError(format!("decimal `{}`: {}", formatted, e))
+                    })?;
+                    float_value(v)
+                }
+                _ => Value::String(formatted),
+            });
+        }
+        Ok(match array.data_type() {
+            DataType::Boolean => Value::from(array.as_boolean().value(i)),
+            DataType::Int8 => Value::from(array.as_primitive::<Int8Type>().value(i)),
+            DataType::Int16 => Value::from(array.as_primitive::<Int16Type>().value(i)),
+            DataType::Int32 => Value::from(array.as_primitive::<Int32Type>().value(i)),
+            DataType::Int64 => Value::from(array.as_primitive::<Int64Type>().value(i)),
+            DataType::UInt8 => Value::from(array.as_primitive::<UInt8Type>().value(i)),
+            DataType::UInt16 => Value::from(array.as_primitive::<UInt16Type>().value(i)),
+            DataType::UInt32 => Value::from(array.as_primitive::<UInt32Type>().value(i)),
+            DataType::UInt64 => Value::from(array.as_primitive::<UInt64Type>().value(i)),
+            DataType::Float16 => float_value(array.as_primitive::<Float16Type>().value(i).to_f64()),
+            DataType::Float32 => {
+                float_value(f64::from(array.as_primitive::<Float32Type>().value(i)))
+            }
+            DataType::Float64 => float_value(array.as_primitive::<Float64Type>().value(i)),
+            DataType::Utf8 => Value::from(array.as_string::<i32>().value(i)),
+            DataType::LargeUtf8 => Value::from(array.as_string::<i64>().value(i)),
+            DataType::List(_) => list_value(array.as_list::<i32>().value(i).as_ref())?,
+            DataType::LargeList(_) => list_value(array.as_list::<i64>().value(i).as_ref())?,
+            DataType::FixedSizeList(_, _) => {
+                list_value(array.as_fixed_size_list().value(i).as_ref())?
+            }
+            DataType::Struct(fields) => {
+                let mut object = Map::new();
+                for (field, column) in fields.iter().zip(&self.children) {
+                    object.insert(field.name().clone(), column.value(i)?);
+                }
+                Value::Object(object)
+            }
+            _ => Value::Null,
+        })
+    }
+}
+
+fn list_value(values: &dyn Array) -> Result<Value, ArrowError> {
+    let column = Column::new(values)?;
+    (0..values.len())
+        .map(|i| column.value(i))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+impl UrlData {
+    /// Build an inline data from an arrow `RecordBatch`, with one object per row. Numbers (including decimals) and
+    /// booleans are kept, temporal values are turned into ISO 8601 strings, nulls into `null` and lists into
+    /// arrays.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<UrlData, ArrowError> {
+        let schema = batch.schema();
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| Column::new(column.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = (0..batch.num_rows())
+            .map(|row| {
+                let mut object = Map::new();
+                for (field, column) in schema.fields().iter().zip(&columns) {
+                    object.insert(field.name().clone(), column.value(row)?);
+                }
+                Ok(Value::Object(object))
+            })
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        UrlDataBuilder::default()
+            .values(UrlDataInlineDataset::UnionArray(values))
+            .build()
+            .map_err(ArrowError::InvalidArgumentError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn column(array: impl Array + 'static) -> ArrayRef {
+        Arc::new(array)
+    }
+
+    #[test]
+    fn from_record_batch() {
+        let list = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            None,
+        ]);
+        let structs = StructArray::from(vec![(
+            Arc::new(Field::new("s", DataType::Utf8, false)),
+            column(StringArray::from(vec!["x", "y"])),
+        )]);
+        let decimals = Decimal128Array::from(vec![12345, -50])
+            .with_precision_and_scale(5, 2)
+            .unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("int", column(Int32Array::from(vec![Some(1), None]))),
+            ("uint", column(UInt8Array::from(vec![7, 8]))),
+            ("float", column(Float64Array::from(vec![0.5, f64::NAN]))),
+            ("decimal", column(decimals)),
+            ("date", column(Date32Array::from(vec![18262, 0]))),
+            (
+                "timestamp",
+                column(TimestampSecondArray::from(vec![0, 86400])),
+            ),
+            ("bool", column(BooleanArray::from(vec![true, false]))),
+            ("list", column(list)),
+            ("struct", column(structs)),
+            ("null", column(NullArray::new(2))),
+        ])
+        .unwrap();
+        let data = UrlData::from_record_batch(&batch).unwrap();
+        assert_eq!(
+            serde_json::to_value(&data).unwrap()["values"],
+            json!([
+                {
+                    "int": 1, "uint": 7, "float": 0.5, "decimal": 123.45, "date": "2020-01-01",
+                    "timestamp": "1970-01-01T00:00:00", "bool": true, "list": [1, 2], "struct": {"s": "x"},
+                    "null": null,
+                },
+                {
+                    "int": null, "uint": 8, "float": null, "decimal": -0.5, "date": "1970-01-01",
+                    "timestamp": "1970-01-02T00:00:00", "bool": false, "list": null, "struct": {"s": "y"},
+                    "null": null,
+                },
+            ])
+        );
+    }
+}
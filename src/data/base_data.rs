@@ -9,11 +9,17 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use crate::DataFormat;
+use crate::DataFormatType;
 use crate::RemovableValue;
 use crate::UrlData;
 use crate::UrlDataBuilder;
 use crate::UrlDataInlineDataset;
+use crate::VegaliteBuilder;
 use serde::Serialize;
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::fmt;
 
 /// Helper method turning an iterator over a `Serialize`-able type into a data that can't be used in a graph.
 pub fn iter_to_data<T>(v: impl Iterator<Item = T>) -> UrlData
@@ -71,3 +77,182 @@ where
         RemovableValue::Specified(v.into())
     }
 }
+
+/// How a field of the data is parsed, see [`DataFormat::parse_field`](struct.DataFormat.html#method.parse_field)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseType {
+    /// Parse as a number
+    Number,
+    /// Parse as a boolean
+    Boolean,
+    /// Parse as a date, with javascript `Date.parse()`
+    Date,
+    /// Parse as a local date, with a [d3-time-format](https://github.com/d3/d3-time-format#locale_format)
+    /// specifier (for example `"%d/%m/%Y"`)
+    DateFormat(String),
+    /// Parse as a UTC date, with a [d3-time-format](https://github.com/d3/d3-time-format#locale_format) specifier
+    Utc(String),
+}
+
+impl fmt::Display for ParseType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseType::Number => write!(f, "number"),
+            ParseType::Boolean => write!(f, "boolean"),
+            ParseType::Date => write!(f, "date"),
+            ParseType::DateFormat(format) => write!(f, "date:'{}'", format),
+            ParseType::Utc(format) => write!(f, "utc:'{}'", format),
+        }
+    }
+}
+
+impl DataFormat {
+    /// Parse `field` as `ty` instead of relying on type inference.
+    pub fn parse_field(mut self, field: &str, ty: ParseType) -> Self {
+        self.parse
+            .get_or_insert_default()
+            .insert(field.to_string(), Some(ty.to_string()));
+        self
+    }
+
+    /// Disable the type inference of every field, serialized as `"parse": null`. Fields are kept as parsed by the
+    /// format, e.g. as strings for csv.
+    pub fn no_parse(mut self) -> Self {
+        self.parse = RemovableValue::Remove;
+        self
+    }
+}
+
+impl UrlData {
+    /// Build an inline data from a json value already at hand, without serializing it again. The value must be an
+    /// array (of objects or primitive values) or an object, any other value is rejected.
+    pub fn from_json_value(v: Value) -> Result<UrlData, String> {
+        let values = match v {
+            Value::Array(values) => UrlDataInlineDataset::UnionArray(values),
+            Value::Object(map) => UrlDataInlineDataset::AnythingMap(
+                map.into_iter()
+                    .map(|(k, v)| match v {
+                        Value::Null => (k, None),
+                        v => (k, Some(v)),
+                    })
+                    .collect(),
+            ),
+            other => {
+                return Err(format!(
+                    "inline data values must be a json array or object, got `{}`",
+                    other
+                ))
+            }
+        };
+        UrlDataBuilder::default().values(values).build()
+    }
+
+    /// Data loaded from `url`, parsed as `format`. Useful when the format can't be inferred from the extension of
+    /// the url, for example for an API endpoint with a query string.
+    pub fn url_with_format(url: &str, format: DataFormatType) -> UrlData {
+        UrlData {
+            url: Some(url.to_string()),
+            format: Some(DataFormat {
+                data_format_type: Some(format),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// A data source with only a `name`, and no values. The data is bound at runtime through the Vega view API,
+    /// which allows to update a chart after it is loaded, for live dashboards. With vega-embed:
+    ///
+    /// ```js
+    /// const result = await vegaEmbed("#vis", spec);
+    /// result.view
+    ///   .change(name, vega.changeset().insert(newRows).remove(() => true))
+    ///   .run();
+    /// ```
+    ///
+    /// The chart is empty until data is inserted for `name`.
+    pub fn named_source(name: &str) -> UrlData {
+        UrlData {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Parse `field` as `ty` instead of relying on type inference, see
+    /// [`DataFormat::parse_field`](struct.DataFormat.html#method.parse_field).
+    pub fn parse(mut self, field: &str, ty: ParseType) -> Self {
+        let format = self.format.take().unwrap_or_default();
+        self.format = Some(format.parse_field(field, ty));
+        self
+    }
+}
+
+impl VegaliteBuilder {
+    /// Load the data of the chart from `url`, parsed as csv whatever the extension of the url.
+    pub fn data_url_csv(&mut self, url: &str) -> &mut Self {
+        self.data(UrlData::url_with_format(url, DataFormatType::Csv))
+    }
+
+    /// Load the data of the chart from `url`, parsed as json whatever the extension of the url.
+    pub fn data_url_json(&mut self, url: &str) -> &mut Self {
+        self.data(UrlData::url_with_format(url, DataFormatType::Json))
+    }
+}
+
+impl TryFrom<Value> for UrlData {
+    type Error = String;
+
+    fn try_from(v: Value) -> Result<Self, Self::Error> {
+        UrlData::from_json_value(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn json_array_and_object_are_accepted() {
+        let data = UrlData::from_json_value(json!([{"a": 1}, {"a": 2}])).unwrap();
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            json!({"values": [{"a": 1}, {"a": 2}]})
+        );
+        let data = UrlData::try_from(json!({"a": 1, "b": null})).unwrap();
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            json!({"values": {"a": 1, "b": null}})
+        );
+    }
+
+    #[test]
+    fn json_scalars_are_rejected() {
+        assert_eq!(
+            UrlData::from_json_value(json!(1)).unwrap_err(),
+            "inline data values must be a json array or object, got `1`"
+        );
+        assert!(UrlData::try_from(json!("a")).is_err());
+    }
+
+    #[test]
+    fn parse_fields() {
+        let data = UrlData::url_with_format("data.csv", DataFormatType::Csv)
+            .parse("a", ParseType::Number)
+            .parse("b", ParseType::Boolean)
+            .parse("c", ParseType::Date)
+            .parse("d", ParseType::DateFormat("%d/%m/%Y".to_string()))
+            .parse("e", ParseType::Utc("%Y-%m-%d %H:%M".to_string()));
+        assert_eq!(
+            serde_json::to_value(&data).unwrap()["format"]["parse"],
+            json!({
+                "a": "number",
+                "b": "boolean",
+                "c": "date",
+                "d": "date:'%d/%m/%Y'",
+                "e": "utc:'%Y-%m-%d %H:%M'",
+            })
+        );
+    }
+}
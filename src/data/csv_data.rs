@@ -3,6 +3,7 @@ use crate::UrlData;
 use crate::UrlDataBuilder;
 use crate::UrlDataInlineDataset;
 use csv::Reader;
+use serde_json::{Map, Number, Value};
 
 #[cfg(feature = "csv")]
 impl<R> From<Reader<R>> for UrlData
@@ -14,10 +15,10 @@ where
             .values(UrlDataInlineDataset::UnionArray(
                 v.records()
                     .map(|it: Result<csv::StringRecord, _>| {
-                        serde_json::Value::Array(
+                        Value::Array(
                             it.expect("TODO manage error in csv")
                                 .iter()
-                                .map(|f: &str| serde_json::Value::String(f.to_string()))
+                                .map(|f: &str| Value::String(f.to_string()))
                                 .collect::<Vec<_>>(),
                         )
                     })
@@ -37,3 +38,101 @@ where
         RemovableValue::Specified(v.into())
     }
 }
+
+// a number that can be read back as written: a leading zero (`007`, a ZIP code or an id) is kept as a string,
+// as it would be lost by reading it as a number
+#[cfg(feature = "csv")]
+fn is_number(cell: &str) -> bool {
+    let digits = cell.trim_start_matches(['-', '+']);
+    let leading_zero =
+        digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit());
+    !leading_zero && cell.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+#[cfg(feature = "csv")]
+impl UrlData {
+    /// Build inline data from a csv string, for quick examples and tests. The first line must be a header with
+    /// the name of each column. A column whose non-empty cells are all numbers is kept as numbers, any other
+    /// column as strings. Empty cells are `null`. Numbers with a leading zero (`007`) are not read as numbers, so that
+    /// ids and ZIP codes are kept as written.
+    pub fn from_csv_str(csv: &str) -> Result<UrlData, csv::Error> {
+        let mut reader = Reader::from_reader(csv.as_bytes());
+        let headers = reader.headers()?.clone();
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+        let numeric: Vec<bool> = (0..headers.len())
+            .map(|i| {
+                records
+                    .iter()
+                    .filter_map(|record| record.get(i))
+                    .filter(|cell| !cell.is_empty())
+                    .all(is_number)
+            })
+            .collect();
+        let rows = records
+            .iter()
+            .map(|record| {
+                let row: Map<String, Value> = headers
+                    .iter()
+                    .zip(record.iter())
+                    .zip(&numeric)
+                    .map(|((name, cell), &numeric)| {
+                        let value = if cell.is_empty() {
+                            Value::Null
+                        } else if let (true, Ok(n)) = (numeric, cell.parse::<i64>()) {
+                            Value::from(n)
+                        } else if numeric {
+                            cell.parse::<f64>()
+                                .ok()
+                                .and_then(Number::from_f64)
+                                .map_or(Value::Null, Value::Number)
+                        } else {
+                            Value::String(cell.to_string())
+                        };
+                        (name.to_string(), value)
+                    })
+                    .collect();
+                Value::Object(row)
+            })
+            .collect();
+        Ok(UrlData {
+            values: Some(UrlDataInlineDataset::UnionArray(rows)),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(all(test, feature = "csv"))]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    fn values(csv: &str) -> serde_json::Value {
+        serde_json::to_value(UrlData::from_csv_str(csv).unwrap()).unwrap()["values"].clone()
+    }
+
+    #[test]
+    fn column_types() {
+        let csv = "int,float,mixed,empty\n1,1.5,x,\n-2,0,3,\n3,-0.25,,\n";
+        assert_eq!(
+            values(csv),
+            json!([
+                {"int": 1, "float": 1.5, "mixed": "x", "empty": null},
+                {"int": -2, "float": 0, "mixed": "3", "empty": null},
+                {"int": 3, "float": -0.25, "mixed": null, "empty": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn leading_zeros_are_kept() {
+        let csv = "id,zip\n007,02134\n,90210\n12,00501\n";
+        assert_eq!(
+            values(csv),
+            json!([
+                {"id": "007", "zip": "02134"},
+                {"id": null, "zip": "90210"},
+                {"id": "12", "zip": "00501"},
+            ])
+        );
+    }
+}
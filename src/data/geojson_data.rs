@@ -0,0 +1,26 @@
+use crate::DataFormat;
+use crate::DataFormatType;
+use crate::UrlData;
+use crate::UrlDataInlineDataset;
+use geojson::{FeatureCollection, JsonObject};
+
+impl UrlData {
+    /// Build inline data from a GeoJSON feature collection, to draw with a `geoshape` mark. The collection is
+    /// embedded as json, and its `features` are used as the rows of the data, so that the properties of a feature
+    /// are available as `properties.<name>` fields.
+    pub fn from_geojson(fc: &FeatureCollection) -> UrlData {
+        let collection = JsonObject::from(fc)
+            .into_iter()
+            .map(|(k, v)| (k, if v.is_null() { None } else { Some(v) }))
+            .collect();
+        UrlData {
+            values: Some(UrlDataInlineDataset::AnythingMap(collection)),
+            format: Some(DataFormat {
+                data_format_type: Some(DataFormatType::Json),
+                property: Some("features".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
@@ -20,3 +20,13 @@ pub use ndarray_data::*;
 mod csv_data;
 #[cfg(feature = "csv")]
 pub use csv_data::*;
+
+#[cfg(feature = "arrow")]
+mod arrow_data;
+#[cfg(feature = "arrow")]
+pub use arrow_data::*;
+
+#[cfg(feature = "geojson")]
+mod geojson_data;
+#[cfg(feature = "geojson")]
+pub use geojson_data::*;
@@ -0,0 +1,123 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+impl DateTime {
+    /// The start of `year`.
+    pub fn year(year: i32) -> Self {
+        DateTime {
+            year: Some(f64::from(year)),
+            ..Default::default()
+        }
+    }
+
+    /// The start of the day `date` (from 1 to 31) of `month` (from 1 for January to 12) of `year`.
+    pub fn ymd(year: i32, month: u32, date: u32) -> Self {
+        DateTime {
+            year: Some(f64::from(year)),
+            month: Some(Month::Double(f64::from(month))),
+            date: Some(f64::from(date)),
+            ..Default::default()
+        }
+    }
+
+    /// The start of `month` of `year`. `month` is either a number (`1.0` for January) or a case-insensitive name,
+    /// full (`"January"`) or short (`"Jan"`).
+    pub fn year_month(year: i32, month: impl Into<Month>) -> Self {
+        DateTime {
+            year: Some(f64::from(year)),
+            month: Some(month.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A day of the week, either a number (`1.0` for Monday) or a case-insensitive name, full (`"Monday"`) or
+    /// short (`"Mon"`). It can't be combined with a year, quarter, month or date.
+    pub fn weekday(day: impl Into<Day>) -> Self {
+        DateTime {
+            day: Some(day.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Convert a chrono date time, to the millisecond. It's converted to UTC so that it represents the same instant
+    /// whatever the time zone of the browser rendering the chart.
+    #[cfg(feature = "chrono")]
+    pub fn from_chrono<Tz: chrono::TimeZone>(datetime: &chrono::DateTime<Tz>) -> Self {
+        use chrono::{Datelike, Timelike};
+
+        let datetime = datetime.with_timezone(&chrono::Utc);
+        DateTime {
+            year: Some(f64::from(datetime.year())),
+            month: Some(Month::Double(f64::from(datetime.month()))),
+            date: Some(f64::from(datetime.day())),
+            hours: Some(f64::from(datetime.hour())),
+            minutes: Some(f64::from(datetime.minute())),
+            seconds: Some(f64::from(datetime.second())),
+            // leap seconds are represented with nanoseconds over one second
+            milliseconds: Some(f64::from(datetime.nanosecond() / 1_000_000 % 1000)),
+            utc: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    fn to_json(datetime: DateTime) -> serde_json::Value {
+        serde_json::to_value(datetime).unwrap()
+    }
+
+    #[test]
+    fn ymd() {
+        assert_eq!(
+            to_json(DateTime::ymd(2020, 2, 29)),
+            json!({"year": 2020.0, "month": 2.0, "date": 29.0})
+        );
+    }
+
+    #[test]
+    fn month_and_day_names() {
+        assert_eq!(
+            to_json(DateTime::year_month(2020, "Jan")),
+            json!({"year": 2020.0, "month": "Jan"})
+        );
+        assert_eq!(
+            to_json(DateTime::year_month(2020, 3.0)),
+            json!({"year": 2020.0, "month": 3.0})
+        );
+        assert_eq!(to_json(DateTime::weekday("Mon")), json!({"day": "Mon"}));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn from_chrono_is_utc() {
+        use chrono::{FixedOffset, TimeZone};
+
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let datetime = offset
+            .with_ymd_and_hms(2020, 1, 1, 1, 30, 15)
+            .unwrap()
+            .checked_add_signed(chrono::Duration::milliseconds(250))
+            .unwrap();
+        assert_eq!(
+            to_json(DateTime::from_chrono(&datetime)),
+            json!({
+                "year": 2019.0, "month": 12.0, "date": 31.0, "hours": 23.0, "minutes": 30.0, "seconds": 15.0,
+                "milliseconds": 250.0, "utc": true,
+            })
+        );
+    }
+}
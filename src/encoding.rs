@@ -0,0 +1,305 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use crate::view::EncodingMut;
+use serde::Serialize;
+
+// for position channels (`x` and `y`)
+macro_rules! position_channel_helpers{
+    ( $( $x:ident ),* $(,)? ) => {
+            $(
+                impl $x {
+                    /// Set whether the scale domain should include zero.
+                    pub fn scale_zero(mut self, zero: bool) -> Self {
+                        self.scale.get_or_insert_default().zero = Some(zero);
+                        self
+                    }
+
+                    /// Set whether values outside the scale domain should be clamped to the range.
+                    pub fn scale_clamp(mut self, clamp: bool) -> Self {
+                        self.scale.get_or_insert_default().clamp = Some(clamp);
+                        self
+                    }
+
+                    /// Set the padding of the scale, in pixels for continuous scales or as a fraction of the step
+                    /// for band and point scales.
+                    pub fn scale_padding(mut self, padding: f64) -> Self {
+                        self.scale.get_or_insert_default().padding = Some(padding);
+                        self
+                    }
+
+                    /// Set the maximum length of the axis labels, in pixels. Longer labels are truncated.
+                    pub fn axis_label_limit(mut self, limit: f64) -> Self {
+                        self.axis.get_or_insert_default().label_limit = Some(limit);
+                        self
+                    }
+
+                    /// Set the maximum length of the axis title, in pixels. A longer title is truncated.
+                    pub fn axis_title_limit(mut self, limit: f64) -> Self {
+                        self.axis.get_or_insert_default().title_limit = Some(limit);
+                        self
+                    }
+
+                    /// Fill the missing values of this channel with `value`, for each value of the other position
+                    /// channel and each group, so that lines and areas go through `value` instead of bridging the
+                    /// gaps.
+                    pub fn impute_value(mut self, value: impl Into<serde_json::Value>) -> Self {
+                        self.impute = Some(ImputeParams {
+                            value: Some(value.into()),
+                            ..Default::default()
+                        });
+                        self
+                    }
+
+                    /// Fill the missing values of this channel with `null`, so that lines and areas are broken at
+                    /// the gaps instead of bridging them.
+                    pub fn impute_none(self) -> Self {
+                        self.impute_value(serde_json::Value::Null)
+                    }
+                }
+            )*
+    };
+}
+
+position_channel_helpers!(XClass, YClass);
+
+// for channels that can have a legend
+macro_rules! legend_channel_helpers{
+    ( $( $x:ident ),* $(,)? ) => {
+            $(
+                impl $x {
+                    /// Set the maximum length of the legend labels, in pixels. Longer labels are truncated.
+                    pub fn legend_label_limit(mut self, limit: f64) -> Self {
+                        self.legend.get_or_insert_default().label_limit = Some(limit);
+                        self
+                    }
+
+                    /// Set the maximum length of the legend title, in pixels. A longer title is truncated.
+                    pub fn legend_title_limit(mut self, limit: f64) -> Self {
+                        self.legend.get_or_insert_default().title_limit = Some(limit);
+                        self
+                    }
+                }
+            )*
+    };
+}
+
+legend_channel_helpers!(
+    DefWithConditionMarkPropFieldDefGradientStringNull,
+    DefWithConditionMarkPropFieldDefNumber,
+    DefWithConditionMarkPropFieldDefTypeForShapeStringNull,
+);
+
+// for encodings, that can have `x` and `y` channels
+macro_rules! encoding_builder_helpers{
+    ( $( $x:ident ),* $(,)? ) => {
+            $(
+                impl $x {
+                    /// Set `x` to the count of records. No field is needed to count.
+                    pub fn x_count(&mut self) -> &mut Self {
+                        self.x(XClass {
+                            aggregate: Some(NonArgAggregateOp::Count.into()),
+                            def_type: Some(StandardType::Quantitative),
+                            ..Default::default()
+                        })
+                    }
+
+                    /// Set `y` to the count of records. No field is needed to count.
+                    pub fn y_count(&mut self) -> &mut Self {
+                        self.y(YClass {
+                            aggregate: Some(NonArgAggregateOp::Count.into()),
+                            def_type: Some(StandardType::Quantitative),
+                            ..Default::default()
+                        })
+                    }
+                }
+            )*
+    };
+}
+
+encoding_builder_helpers!(EncodingBuilder, LayerEncodingBuilder);
+
+// for encodings, with the channels that can have a legend
+macro_rules! encoding_legends{
+    ( $x:ident : $( $channel:ident ),* $(,)? ) => {
+        impl $x {
+            fn remove_legends(&mut self) {
+                $(
+                    if let Some(def) = self.$channel.as_mut() {
+                        def.legend = RemovableValue::Remove;
+                    }
+                )*
+            }
+        }
+    };
+}
+
+encoding_legends!(Encoding: color, fill, stroke, opacity, fill_opacity, stroke_opacity, stroke_width, size, shape);
+encoding_legends!(LayerEncoding: color, fill, stroke, opacity, fill_opacity, stroke_opacity, stroke_width, size, shape);
+
+// a channel of an encoding, whether it's optional or removable
+trait ChannelSlot {
+    // set to a definition, not removed with `null`
+    fn is_active(&self) -> bool;
+    fn to_json(&self) -> Option<serde_json::Value>;
+}
+
+impl<T: Serialize> ChannelSlot for Option<T> {
+    fn is_active(&self) -> bool {
+        self.is_some()
+    }
+
+    fn to_json(&self) -> Option<serde_json::Value> {
+        self.as_ref().and_then(|def| serde_json::to_value(def).ok())
+    }
+}
+
+impl<T: Clone + Serialize> ChannelSlot for RemovableValue<T> {
+    fn is_active(&self) -> bool {
+        matches!(self, RemovableValue::Specified(_))
+    }
+
+    fn to_json(&self) -> Option<serde_json::Value> {
+        if !self.is_default() {
+            serde_json::to_value(self).ok()
+        } else {
+            None
+        }
+    }
+}
+
+// reflection over the channels of an encoding, by their Vega-Lite name
+macro_rules! encoding_channels{
+    ( $x:ident : $( $channel:ident => $name:literal ),* $(,)? ) => {
+        impl $x {
+            /// The Vega-Lite names of the channels that are set (for example `"x"` or `"fillOpacity"`). A channel
+            /// explicitly removed with `null` (like `tooltip`) isn't active.
+            pub fn active_channels(&self) -> Vec<&'static str> {
+                let mut channels = Vec::new();
+                $(
+                    if self.$channel.is_active() {
+                        channels.push($name);
+                    }
+                )*
+                channels
+            }
+
+            /// The json definition of a channel from its Vega-Lite name (for example `"x"` or `"fillOpacity"`),
+            /// `None` if the channel doesn't exist or is not set. A channel removed with `null` is `Some(Value::Null)`.
+            pub fn get(&self, channel: &str) -> Option<serde_json::Value> {
+                match channel {
+                    $( $name => self.$channel.to_json(), )*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+encoding_channels!(Encoding:
+    color => "color", column => "column", detail => "detail", facet => "facet", fill => "fill",
+    fill_opacity => "fillOpacity", href => "href", key => "key", latitude => "latitude", latitude2 => "latitude2",
+    longitude => "longitude", longitude2 => "longitude2", opacity => "opacity", order => "order", row => "row",
+    shape => "shape", size => "size", stroke => "stroke", stroke_opacity => "strokeOpacity",
+    stroke_width => "strokeWidth", text => "text", tooltip => "tooltip", url => "url", x => "x", x2 => "x2",
+    x_error => "xError", x_error2 => "xError2", y => "y", y2 => "y2", y_error => "yError", y_error2 => "yError2",
+);
+encoding_channels!(LayerEncoding:
+    color => "color", detail => "detail", fill => "fill", fill_opacity => "fillOpacity", href => "href",
+    key => "key", latitude => "latitude", latitude2 => "latitude2", longitude => "longitude",
+    longitude2 => "longitude2", opacity => "opacity", order => "order", shape => "shape", size => "size",
+    stroke => "stroke", stroke_opacity => "strokeOpacity", stroke_width => "strokeWidth", text => "text",
+    tooltip => "tooltip", url => "url", x => "x", x2 => "x2", x_error => "xError", x_error2 => "xError2", y => "y",
+    y2 => "y2", y_error => "yError", y_error2 => "yError2",
+);
+
+impl Vegalite {
+    /// Remove the legends of every channel in every view of the chart, by setting them to `null`. Vega-Lite 4.0
+    /// config can't disable legends globally, so this is done on the encodings.
+    pub fn no_legends(mut self) -> Self {
+        self.visit_views_mut(&mut |mut view| match view.encoding() {
+            Some(EncodingMut::Unit(encoding)) => encoding.remove_legends(),
+            Some(EncodingMut::Layer(encoding)) => encoding.remove_legends(),
+            None => (),
+        });
+        self
+    }
+}
+
+// for views, with the type of their encoding
+macro_rules! encoding_mut{
+    ( $( $x:ident => $e:ident ),* $(,)? ) => {
+            $(
+                impl $x {
+                    /// Get the encoding, creating an empty one first if there is none yet.
+                    pub fn encoding_mut(&mut self) -> &mut $e {
+                        self.encoding.get_or_insert_with(Default::default)
+                    }
+                }
+            )*
+    };
+}
+
+encoding_mut!(
+    Vegalite => Encoding,
+    Spec => Encoding,
+    SpecClass => Encoding,
+    LayerSpec => LayerEncoding,
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn count_channels() {
+        let encoding = EncodingBuilder::default().y_count().build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&encoding).unwrap(),
+            json!({"y": {"aggregate": "count", "type": "quantitative"}})
+        );
+        let encoding = LayerEncodingBuilder::default().x_count().build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&encoding).unwrap(),
+            json!({"x": {"aggregate": "count", "type": "quantitative"}})
+        );
+    }
+
+    #[test]
+    fn active_channels() {
+        let mut chart = test_charts::bar_chart();
+        let encoding = chart.encoding_mut();
+        encoding.tooltip = RemovableValue::Remove;
+        assert_eq!(encoding.active_channels(), vec!["x", "y"]);
+        assert_eq!(encoding.get("x"), Some(json!({"field": "a"})));
+        assert_eq!(encoding.get("tooltip"), Some(serde_json::Value::Null));
+        assert_eq!(encoding.get("color"), None);
+        assert_eq!(encoding.get("unknown"), None);
+    }
+
+    #[test]
+    fn active_layer_channels() {
+        let encoding = LayerEncoding {
+            fill_opacity: Some(DefWithConditionMarkPropFieldDefNumber {
+                value: Some(0.5),
+                ..Default::default()
+            }),
+            tooltip: RemovableValue::Remove,
+            ..Default::default()
+        };
+        assert_eq!(encoding.active_channels(), vec!["fillOpacity"]);
+        assert_eq!(encoding.get("fillOpacity"), Some(json!({"value": 0.5})));
+        assert_eq!(encoding.get("row"), None);
+    }
+}
@@ -0,0 +1,378 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use crate::view::EncodingMut;
+use serde_json::Value as Json;
+
+/// The `$schema` set by `VegaliteBuilder` by default
+pub(crate) const DEFAULT_SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v4.0.2.json";
+
+// does this predicate (or one of the predicates it combines) use a selection
+fn uses_selection(value: &Json) -> bool {
+    match value {
+        Json::Object(map) => map.contains_key("selection") || map.values().any(uses_selection),
+        Json::Array(values) => values.iter().any(uses_selection),
+        _ => false,
+    }
+}
+
+// does this predicate use a selection
+fn predicate_uses_selection(predicate: &PurpleLogicalOperandPredicate) -> bool {
+    serde_json::to_value(predicate).is_ok_and(|json| uses_selection(&json))
+}
+
+// a condition of a conditional encoding
+trait SelectionCondition {
+    fn uses_selection(&self) -> bool;
+}
+
+macro_rules! selection_condition {
+    ( $( $x:ident ),* $(,)? ) => {
+        $(
+            impl SelectionCondition for $x {
+                fn uses_selection(&self) -> bool {
+                    self.selection.is_some() || self.test.as_ref().is_some_and(predicate_uses_selection)
+                }
+            }
+        )*
+    };
+}
+
+selection_condition!(
+    ConditionalPredicateValueDefGradientStringNullClass,
+    ConditionalValueDefGradientStringNull,
+    ConditionalDef,
+    ConditionalNumberValueDef,
+    ConditionElement,
+    ConditionalPredicateValueDefStringClass,
+    ConditionalPredicateMarkPropFieldDefTypeForShapeClass,
+    ConditionalStringValueDef,
+    ConditionalPredicateValueDefTextClass,
+    ConditionalValueDefText,
+);
+
+// a channel definition, or an encoding, that may use a selection
+trait StripSelections {
+    fn strip_selections(&mut self);
+}
+
+impl<T: StripSelections> StripSelections for Option<T> {
+    fn strip_selections(&mut self) {
+        if let Some(def) = self {
+            def.strip_selections();
+        }
+    }
+}
+
+// remove a scale domain bound to a selection
+fn strip_domain(scale: &mut RemovableValue<Scale>) {
+    if let RemovableValue::Specified(scale) = scale {
+        if let Some(DomainUnion::DomainClass(domain)) = &scale.domain {
+            if domain.selection.is_some() {
+                scale.domain = None;
+            }
+        }
+    }
+}
+
+// for channel definitions, with the variants of their condition (a single condition or a list of conditions),
+// followed by `; scale` if they have a scale
+macro_rules! strip_channel {
+    ( $x:ident $( , condition: $single:path, $list:path )? $( ; $scale:ident )? ) => {
+        impl StripSelections for $x {
+            fn strip_selections(&mut self) {
+                $(
+                    let remove = match self.condition.as_mut() {
+                        Some($single(condition)) => condition.uses_selection(),
+                        Some($list(conditions)) => {
+                            conditions.retain(|c| !c.uses_selection());
+                            conditions.is_empty()
+                        }
+                        None => false,
+                    };
+                    if remove {
+                        self.condition = None;
+                    }
+                )?
+                $(
+                    strip_domain(&mut self.$scale);
+                )?
+            }
+        }
+    };
+}
+
+strip_channel!(
+    DefWithConditionMarkPropFieldDefGradientStringNull,
+    condition: ColorCondition::ConditionalPredicateValueDefGradientStringNullClass,
+    ColorCondition::ConditionalValueDefGradientStringNullArray;
+    scale
+);
+strip_channel!(
+    DefWithConditionMarkPropFieldDefNumber,
+    condition: ConditionUnion::ConditionalDef,
+    ConditionUnion::ConditionalNumberValueDefArray;
+    scale
+);
+strip_channel!(
+    DefWithConditionMarkPropFieldDefTypeForShapeStringNull,
+    condition: ShapeCondition::ConditionalPredicateMarkPropFieldDefTypeForShapeClass,
+    ShapeCondition::ConditionalStringValueDefArray;
+    scale
+);
+strip_channel!(
+    DefWithConditionStringFieldDefText,
+    condition: TextCondition::ConditionalPredicateValueDefTextClass,
+    TextCondition::ConditionalValueDefTextArray
+);
+strip_channel!(
+    HrefClass,
+    condition: HrefCondition::ConditionalPredicateValueDefStringClass,
+    HrefCondition::ConditionElementArray
+);
+strip_channel!(
+    FieldDefWithConditionStringFieldDefString,
+    condition: HrefCondition::ConditionalPredicateValueDefStringClass,
+    HrefCondition::ConditionElementArray
+);
+strip_channel!(XClass; scale);
+strip_channel!(YClass; scale);
+
+impl StripSelections for Tooltip {
+    fn strip_selections(&mut self) {
+        if let Tooltip::FieldDefWithConditionStringFieldDefString(def) = self {
+            def.strip_selections();
+        }
+    }
+}
+
+impl<T: StripSelections + Clone> StripSelections for RemovableValue<T> {
+    fn strip_selections(&mut self) {
+        if let RemovableValue::Specified(def) = self {
+            def.strip_selections();
+        }
+    }
+}
+
+// for encodings, with their channels that may use a selection
+macro_rules! strip_encoding {
+    ( $x:ident : $( $channel:ident ),* $(,)? ) => {
+        impl StripSelections for $x {
+            fn strip_selections(&mut self) {
+                $(
+                    self.$channel.strip_selections();
+                )*
+            }
+        }
+    };
+}
+
+strip_encoding!(Encoding:
+    color, fill, fill_opacity, href, opacity, shape, size, stroke, stroke_opacity, stroke_width, text, tooltip, url,
+    x, y,
+);
+strip_encoding!(LayerEncoding:
+    color, fill, fill_opacity, href, opacity, shape, size, stroke, stroke_opacity, stroke_width, text, tooltip, url,
+    x, y,
+);
+
+// is this transform a filter by a selection, or a lookup from a selection
+fn transform_uses_selection(transform: &Transform) -> bool {
+    transform
+        .filter
+        .as_ref()
+        .is_some_and(predicate_uses_selection)
+        || transform
+            .from
+            .as_ref()
+            .is_some_and(|from| from.selection.is_some())
+}
+
+impl Vegalite {
+    /// Make the chart explicit and static, so that it renders the same way to a static image (PNG / SVG) whatever
+    /// the renderer defaults:
+    /// * the default `$schema` is set if missing,
+    /// * the `config.background` is set, to the chart `background` or to white,
+    /// * the interactivity is removed with [`strip_interactivity`](#method.strip_interactivity): selections do
+    ///   nothing in a static image, and the conditions, filters and scale domains using them are removed so that
+    ///   the image doesn't depend on the initial state of a selection.
+    ///
+    /// A warning is returned for every selection removed.
+    pub fn sanitize_for_export(&mut self) -> Vec<String> {
+        if self.schema.is_none() {
+            self.schema = Some(DEFAULT_SCHEMA.to_string());
+        }
+        let background = self
+            .background
+            .clone()
+            .unwrap_or_else(|| "white".to_string());
+        self.config
+            .get_or_insert_with(Default::default)
+            .background
+            .get_or_insert(background);
+
+        let mut warnings = Vec::new();
+        self.visit_views_mut(&mut |mut view| {
+            let mut names: Vec<&String> = view.selection().iter().flat_map(|s| s.keys()).collect();
+            names.sort();
+            for name in names {
+                warnings.push(format!(
+                    "removed selection `{}`, it does nothing in a static export",
+                    name
+                ));
+            }
+        });
+        self.strip_interactivity();
+        warnings
+    }
+
+    /// Remove the interactivity of the chart, for a static rendering (PNG / SVG) where it does nothing and may
+    /// cause renderer warnings: the selections of every view are removed, with everything using them:
+    /// * conditional encodings based on a selection collapse to their default (`else`) value, conditions based on
+    ///   a `test` are kept,
+    /// * `filter` transforms by a selection and `lookup` transforms from a selection are removed, so all the data
+    ///   is shown,
+    /// * scale domains bound to a selection are removed.
+    pub fn strip_interactivity(&mut self) {
+        self.visit_views_mut(&mut |mut view| {
+            *view.selection() = None;
+            if let Some(transforms) = view.transform() {
+                transforms.retain(|t| !transform_uses_selection(t));
+            }
+            match view.encoding() {
+                Some(EncodingMut::Unit(encoding)) => encoding.strip_selections(),
+                Some(EncodingMut::Layer(encoding)) => encoding.strip_selections(),
+                None => (),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DEFAULT_SCHEMA;
+    use crate::*;
+
+    fn selection(name: &str) -> Option<Box<PurpleSelectionOperand>> {
+        Some(Box::new(PurpleSelectionOperand::String(name.to_string())))
+    }
+
+    #[test]
+    fn sanitize_for_export() {
+        let mut chart = Vegalite::default().point_on_fields("brush", &["a"]);
+        chart.background = Some("black".to_string());
+        chart.encoding_mut().size = Some(DefWithConditionMarkPropFieldDefNumber {
+            condition: Some(ConditionUnion::ConditionalDef(ConditionalDef {
+                selection: selection("brush"),
+                value: Some(10.0),
+                ..Default::default()
+            })),
+            value: Some(2.0),
+            ..Default::default()
+        });
+
+        let warnings = chart.sanitize_for_export();
+        assert_eq!(
+            warnings,
+            vec!["removed selection `brush`, it does nothing in a static export"]
+        );
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(json["$schema"], DEFAULT_SCHEMA);
+        assert_eq!(json["config"]["background"], "black");
+        assert!(json.get("selection").is_none());
+        assert_eq!(json["encoding"]["size"], serde_json::json!({"value": 2.0}));
+    }
+
+    #[test]
+    fn strip_interactivity() {
+        let color = DefWithConditionMarkPropFieldDefGradientStringNull {
+            condition: Some(ColorCondition::ConditionalValueDefGradientStringNullArray(
+                vec![
+                    ConditionalValueDefGradientStringNull {
+                        selection: selection("brush"),
+                        value: Some(ValueUnion::String("red".to_string())),
+                        ..Default::default()
+                    },
+                    ConditionalValueDefGradientStringNull {
+                        test: Some(PurpleLogicalOperandPredicate::String(
+                            "datum.b > 40".to_string(),
+                        )),
+                        value: Some(ValueUnion::String("blue".to_string())),
+                        ..Default::default()
+                    },
+                ],
+            )),
+            value: Some(ValueUnion::String("grey".to_string())),
+            ..Default::default()
+        };
+        let size = DefWithConditionMarkPropFieldDefNumber {
+            condition: Some(ConditionUnion::ConditionalDef(ConditionalDef {
+                selection: selection("brush"),
+                value: Some(10.0),
+                ..Default::default()
+            })),
+            value: Some(2.0),
+            ..Default::default()
+        };
+        let x = XClass {
+            field: Some("a".into()),
+            scale: RemovableValue::Specified(Scale {
+                domain: Some(DomainUnion::DomainClass(DomainClass {
+                    selection: Some("brush".to_string()),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let filter = Transform {
+            filter: Some(PurpleLogicalOperandPredicate::Predicate(Box::new(
+                Predicate {
+                    selection: selection("brush"),
+                    ..Default::default()
+                },
+            ))),
+            ..Default::default()
+        };
+        let mut chart = Vegalite::default().point_on_fields("brush", &["a"]);
+        chart.mark = Some(Mark::Point.into());
+        chart.encoding = Some(Encoding {
+            color: Some(color),
+            size: Some(size),
+            x: Some(x),
+            ..Default::default()
+        });
+        chart.transform = Some(vec![filter, Transform::sample(10)]);
+
+        chart.strip_interactivity();
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("selection").is_none());
+        assert_eq!(json["transform"].as_array().unwrap().len(), 1);
+        assert_eq!(json["transform"][0]["sample"], 10.0);
+        // the condition on the selection is removed, the one on a test is kept
+        assert_eq!(
+            json["encoding"]["color"]["condition"][0]["test"],
+            "datum.b > 40"
+        );
+        assert_eq!(
+            json["encoding"]["color"]["condition"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(json["encoding"]["size"].get("condition").is_none());
+        assert_eq!(json["encoding"]["size"]["value"], 2.0);
+        assert!(json["encoding"]["x"]["scale"].get("domain").is_none());
+    }
+}
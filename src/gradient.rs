@@ -0,0 +1,112 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+impl GradientStop {
+    /// A stop of the given `color` at `offset`, a fraction between `0` and `1` of the gradient.
+    pub fn new(offset: f64, color: &str) -> Self {
+        GradientStop {
+            color: Some(color.to_string()),
+            offset: Some(offset),
+        }
+    }
+}
+
+fn check_stops(stops: &[GradientStop]) -> Result<(), String> {
+    if stops.is_empty() {
+        return Err("a gradient needs at least one stop".to_string());
+    }
+    for stop in stops {
+        match stop.offset {
+            Some(offset) if (0.0..=1.0).contains(&offset) => (),
+            Some(offset) => {
+                return Err(format!(
+                    "gradient stop offset must be between 0 and 1, got {}",
+                    offset
+                ))
+            }
+            None => return Err("gradient stop is missing its offset".to_string()),
+        }
+    }
+    Ok(())
+}
+
+// for every linear gradient struct (they only differ by where they are used)
+macro_rules! linear_gradient_new{
+    ( $( $x:ident ),* $(,)? ) => {
+            $(
+                impl $x {
+                    /// A linear gradient going through `stops`. There must be at least one stop, and each
+                    /// offset must be between `0` and `1`.
+                    pub fn new(stops: Vec<GradientStop>) -> Result<Self, String> {
+                        check_stops(&stops)?;
+                        Ok($x {
+                            gradient: Some(Gradient::Linear),
+                            stops,
+                            ..Default::default()
+                        })
+                    }
+                }
+            )*
+    };
+}
+
+linear_gradient_new!(ValueLinearGradient, ColorLinearGradient, FillLinearGradient);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    fn stops() -> Vec<GradientStop> {
+        vec![
+            GradientStop::new(0.0, "white"),
+            GradientStop::new(1.0, "red"),
+        ]
+    }
+
+    #[test]
+    fn valid_gradients() {
+        let expected = json!({
+            "gradient": "linear",
+            "stops": [{"offset": 0.0, "color": "white"}, {"offset": 1.0, "color": "red"}],
+        });
+        let value = ValueLinearGradient::new(stops()).unwrap();
+        assert_eq!(serde_json::to_value(value).unwrap(), expected);
+        let color = ColorLinearGradient::new(stops()).unwrap();
+        assert_eq!(serde_json::to_value(color).unwrap(), expected);
+        let fill = FillLinearGradient::new(stops()).unwrap();
+        assert_eq!(serde_json::to_value(fill).unwrap(), expected);
+    }
+
+    #[test]
+    fn no_stops() {
+        assert_eq!(
+            ColorLinearGradient::new(vec![]).unwrap_err(),
+            "a gradient needs at least one stop"
+        );
+    }
+
+    #[test]
+    fn offset_out_of_range() {
+        let stops = vec![
+            GradientStop::new(0.0, "white"),
+            GradientStop::new(1.5, "red"),
+        ];
+        assert_eq!(
+            FillLinearGradient::new(stops).unwrap_err(),
+            "gradient stop offset must be between 0 and 1, got 1.5"
+        );
+        let stops = vec![GradientStop::new(-0.1, "white")];
+        assert!(ValueLinearGradient::new(stops).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+use derive_builder::Builder;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Options given to [vega-embed](https://github.com/vega/vega-embed) when rendering a chart in an html page.
+#[derive(Debug, Clone, Serialize, Default, Builder)]
+#[builder(setter(into, strip_option))]
+pub struct EmbedOptions {
+    /// Show the actions menu (export as PNG / SVG, view source, open in the Vega editor). Defaults to `true` in
+    /// vega-embed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub actions: Option<bool>,
+    /// Id of the `<div>` the chart is rendered into. A unique id is generated if not set, so that several charts
+    /// can be embedded in the same page.
+    #[serde(skip)]
+    #[builder(default)]
+    pub container_id: Option<String>,
+    /// Width of the `<div>` the chart is rendered into, in pixels.
+    #[serde(skip)]
+    #[builder(default)]
+    pub container_width: Option<f64>,
+    /// Height of the `<div>` the chart is rendered into, in pixels.
+    #[serde(skip)]
+    #[builder(default)]
+    pub container_height: Option<f64>,
+    /// Minimum width of the `<div>` the chart is rendered into, in pixels, for charts with a `"container"` width.
+    #[serde(skip)]
+    #[builder(default)]
+    pub container_min_width: Option<f64>,
+}
+
+impl EmbedOptions {
+    /// Options hiding the actions menu, for charts embedded in an application.
+    pub fn no_actions() -> Self {
+        EmbedOptions {
+            actions: Some(false),
+            ..Default::default()
+        }
+    }
+
+    fn container_style(&self) -> String {
+        let mut style = Vec::new();
+        if let Some(width) = self.container_width {
+            style.push(format!("width: {}px", width));
+        }
+        if let Some(height) = self.container_height {
+            style.push(format!("height: {}px", height));
+        }
+        if let Some(min_width) = self.container_min_width {
+            style.push(format!("min-width: {}px", min_width));
+        }
+        if style.is_empty() {
+            String::new()
+        } else {
+            format!(r#" style="{}""#, style.join("; "))
+        }
+    }
+}
+
+/// Generate an id for a chart container, unique in this process and unlikely to collide with the ones of another
+/// process.
+fn unique_container_id() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    format!(
+        "vis-{:x}-{}",
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Json to write in a `<script>`: a `</` in a string (e.g. `"</script>"` in the data) would end the script, it's
+/// written `<\/` which is the same string for javascript.
+fn script_json<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    Ok(serde_json::to_string(value)?.replace("</", "<\\/"))
+}
+
+/// Text to write in a double-quoted html attribute.
+fn escape_attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Standard base64 encoding, with padding. Small enough not to pull a dependency for it.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+impl Vegalite {
+    /// Render the chart as a standalone html page, using vega-embed with its default options.
+    pub fn to_html(&self) -> Result<String, serde_json::Error> {
+        self.to_html_with(&EmbedOptions::default())
+    }
+
+    /// Render the chart as a standalone html page, giving `options` to vega-embed. The container id and size set
+    /// in `options` are used for the `<div>` the chart is rendered into.
+    pub fn to_html_with(&self, options: &EmbedOptions) -> Result<String, serde_json::Error> {
+        Ok(VEGA_EMBED_HTML_TEMPLATE
+            .replace("{{ fragment }}", &self.to_html_fragment_with(options)?))
+    }
+
+    /// Render the chart as an html fragment to insert in an existing page: a `<div>` with the id `container_id`
+    /// and the `<script>` rendering the chart into it. The page must load vega, vega-lite and vega-embed itself.
+    pub fn to_html_fragment(&self, container_id: &str) -> Result<String, serde_json::Error> {
+        self.to_html_fragment_with(&EmbedOptions {
+            container_id: Some(container_id.to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Render the chart as an html fragment to insert in an existing page, giving `options` to vega-embed. The
+    /// page must load vega, vega-lite and vega-embed itself.
+    pub fn to_html_fragment_with(
+        &self,
+        options: &EmbedOptions,
+    ) -> Result<String, serde_json::Error> {
+        let container_id = options
+            .container_id
+            .clone()
+            .unwrap_or_else(unique_container_id);
+        Ok(VEGA_EMBED_FRAGMENT_TEMPLATE
+            .replace("{{ container_id }}", &escape_attribute(&container_id))
+            .replace("{{ container_id_as_json }}", &script_json(&container_id)?)
+            .replace("{{ container_style }}", &options.container_style())
+            .replace("{{ embed_options }}", &script_json(options)?)
+            .replace("{{ spec_as_json }}", &script_json(self)?))
+    }
+
+    /// Render the chart as a standalone html page without the vega-embed actions menu.
+    pub fn to_html_no_actions(&self) -> Result<String, serde_json::Error> {
+        self.to_html_with(&EmbedOptions::no_actions())
+    }
+
+    /// Make the chart as wide as its container, but not narrower than `min_width` pixels so that it stays readable
+    /// on narrow screens. Vega-Lite has no minimum width, it's a CSS `min-width` on the container: render the chart
+    /// with the returned options, with [`to_html_with`](#method.to_html_with) or
+    /// [`to_html_fragment_with`](#method.to_html_fragment_with).
+    pub fn set_width_container_with_min(&mut self, min_width: f64) -> EmbedOptions {
+        self.width = Some(HeightUnion::Enum(HeightEnum::Container));
+        self.autosize = Some(Autosize::AutoSizeParams(AutoSizeParams {
+            auto_size_params_type: Some(AutosizeType::Fit),
+            contains: Some(Contains::Padding),
+            ..Default::default()
+        }));
+        EmbedOptions {
+            container_min_width: Some(min_width),
+            ..Default::default()
+        }
+    }
+
+    /// Render the chart as a standalone html page (see [`to_html`](#method.to_html)) encoded in a
+    /// `data:text/html;base64,...` url, to embed it in an `<iframe src="...">` where scripts can't be injected
+    /// in the host page.
+    pub fn to_data_url(&self) -> Result<String, serde_json::Error> {
+        Ok(format!(
+            "data:text/html;base64,{}",
+            base64_encode(self.to_html()?.as_bytes())
+        ))
+    }
+}
+
+const VEGA_EMBED_HTML_TEMPLATE: &str = r#"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <!-- Import Vega 5 & Vega-Lite 4 (does not have to be from CDN) -->
+  <script src="https://cdn.jsdelivr.net/npm/vega@5"></script>
+  <script src="https://cdn.jsdelivr.net/npm/vega-lite@4"></script>
+  <!-- Import vega-embed -->
+  <script src="https://cdn.jsdelivr.net/npm/vega-embed@6"></script>
+</head>
+<body>
+{{ fragment }}
+</body>
+</html>
+"#;
+
+const VEGA_EMBED_FRAGMENT_TEMPLATE: &str = r#"
+<div id="{{ container_id }}"{{ container_style }}></div>
+
+<script type="text/javascript">
+  (function() {
+    var spec = {{ spec_as_json }};
+    var container = document.getElementById({{ container_id_as_json }});
+    vegaEmbed(container, spec, {{ embed_options }}).then(function(result) {
+      // Access the Vega view instance (https://vega.github.io/vega/docs/api/view/) as result.view
+    }).catch(console.error);
+  })();
+</script>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn base64_encode() {
+        assert_eq!(super::base64_encode(b""), "");
+        assert_eq!(super::base64_encode(b"f"), "Zg==");
+        assert_eq!(super::base64_encode(b"fo"), "Zm8=");
+        assert_eq!(super::base64_encode(b"foo"), "Zm9v");
+        assert_eq!(super::base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(super::base64_encode(&[0xfb, 0xff]), "+/8=");
+    }
+
+    #[test]
+    fn html_loads_vega_lite_4() {
+        let html = Vegalite::default().to_html().unwrap();
+        assert!(html.contains("npm/vega-lite@4"));
+        assert!(html.contains("npm/vega-embed@6"));
+    }
+
+    #[test]
+    fn script_end_tag_in_data_is_escaped() {
+        let chart = Vegalite {
+            data: UrlData::from_json_value(json!([{"a": "</script><script>alert(1)</script>"}]))
+                .unwrap()
+                .into(),
+            ..Default::default()
+        };
+        let html = chart.to_html_fragment("vis").unwrap();
+        assert_eq!(html.matches("</script>").count(), 1);
+        assert!(html.contains(r#""<\/script><script>alert(1)<\/script>""#));
+    }
+
+    #[test]
+    fn min_width_is_not_in_the_spec() {
+        let mut chart = Vegalite::default();
+        let options = chart.set_width_container_with_min(300.0);
+        let html = chart.to_html_with(&options).unwrap();
+        assert!(html.contains(r#" style="min-width: 300px""#));
+        let json = chart.to_string().unwrap();
+        assert!(html.contains(&json));
+        assert!(!json.contains("300"));
+        assert!(json.contains(r#""width":"container""#));
+    }
+
+    #[test]
+    fn container_id_is_escaped() {
+        let html = Vegalite::default()
+            .to_html_fragment(r#"a"b'</script>"#)
+            .unwrap();
+        assert!(html.contains(r#"<div id="a&quot;b'&lt;/script&gt;"></div>"#));
+        assert!(html.contains(r#"document.getElementById("a\"b'<\/script>")"#));
+        assert_eq!(html.matches("</script>").count(), 1);
+    }
+}
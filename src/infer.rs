@@ -0,0 +1,314 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use crate::view::EncodingMut;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// the types that can be inferred from the values of a field
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inferred {
+    Quantitative,
+    Temporal,
+    Nominal,
+}
+
+// the inferred type of each field of the inline data
+type FieldTypes = HashMap<String, Inferred>;
+
+// a date as written in json, `2020-01-31` optionally followed by a time
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() >= 10
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && b[4] == b'-'
+        && b[5..7].iter().all(u8::is_ascii_digit)
+        && b[7] == b'-'
+        && b[8..10].iter().all(u8::is_ascii_digit)
+}
+
+// the likely type of a field from its values, `None` if there is no value to infer it from
+fn infer_type<'a>(values: impl Iterator<Item = &'a Value>) -> Option<Inferred> {
+    let mut inferred = None;
+    for value in values {
+        let ty = match value {
+            Value::Null => continue,
+            Value::Number(_) => Inferred::Quantitative,
+            Value::String(s) if is_date(s) => Inferred::Temporal,
+            _ => Inferred::Nominal,
+        };
+        inferred = match inferred {
+            None => Some(ty),
+            Some(previous) if previous == ty => Some(ty),
+            // mixed values are shown as categories
+            Some(_) => Some(Inferred::Nominal),
+        };
+    }
+    inferred
+}
+
+// the likely type of each field of inline data, empty if the data is not an inline array
+fn field_types(data: &UrlData) -> FieldTypes {
+    let rows = match &data.values {
+        Some(UrlDataInlineDataset::UnionArray(rows)) => rows,
+        _ => return FieldTypes::new(),
+    };
+    let mut fields: HashMap<&str, Vec<&Value>> = HashMap::new();
+    for row in rows {
+        if let Value::Object(row) = row {
+            for (field, value) in row {
+                fields.entry(field).or_default().push(value);
+            }
+        }
+    }
+    fields
+        .into_iter()
+        .filter_map(|(field, values)| Some((field.to_string(), infer_type(values.into_iter())?)))
+        .collect()
+}
+
+// the type enums of the channels, that may not accept every inferred type
+trait FromInferred: Sized {
+    fn from_inferred(ty: Inferred) -> Option<Self>;
+}
+
+impl FromInferred for StandardType {
+    fn from_inferred(ty: Inferred) -> Option<Self> {
+        Some(match ty {
+            Inferred::Quantitative => StandardType::Quantitative,
+            Inferred::Temporal => StandardType::Temporal,
+            Inferred::Nominal => StandardType::Nominal,
+        })
+    }
+}
+
+impl FromInferred for LatitudeType {
+    fn from_inferred(ty: Inferred) -> Option<Self> {
+        match ty {
+            Inferred::Quantitative => Some(LatitudeType::Quantitative),
+            _ => None,
+        }
+    }
+}
+
+impl FromInferred for TypeForShape {
+    fn from_inferred(ty: Inferred) -> Option<Self> {
+        match ty {
+            Inferred::Nominal => Some(TypeForShape::Nominal),
+            _ => None,
+        }
+    }
+}
+
+// a channel definition, or a list of them, whose missing type can be filled
+trait FillType {
+    fn fill_type(&mut self, types: &FieldTypes);
+}
+
+// for field definitions, with the field holding their `type`
+macro_rules! fill_type_field_def {
+    ( $( $x:ident => $ty:ident ),* $(,)? ) => {
+        $(
+            impl FillType for $x {
+                fn fill_type(&mut self, types: &FieldTypes) {
+                    if self.$ty.is_some() {
+                        return;
+                    }
+                    let inferred = if self.aggregate.is_some() {
+                        Some(Inferred::Quantitative)
+                    } else if self.time_unit.is_some() {
+                        Some(Inferred::Temporal)
+                    } else {
+                        match &self.field {
+                            Some(Field::String(field)) => types.get(field).copied(),
+                            _ => None,
+                        }
+                    };
+                    self.$ty = inferred.and_then(FromInferred::from_inferred);
+                }
+            }
+        )*
+    };
+}
+
+fill_type_field_def!(
+    DefWithConditionMarkPropFieldDefGradientStringNull => def_with_condition_mark_prop_field_def_gradient_string_null_type,
+    DefWithConditionMarkPropFieldDefNumber => def_with_condition_mark_prop_field_def_number_type,
+    DefWithConditionMarkPropFieldDefTypeForShapeStringNull => def_with_condition_mark_prop_field_def_type_for_shape_string_null_type,
+    DefWithConditionStringFieldDefText => def_with_condition_string_field_def_text_type,
+    FacetEncodingFieldDef => facet_encoding_field_def_type,
+    FieldDefWithConditionStringFieldDefString => field_def_with_condition_string_field_def_string_type,
+    HrefClass => field_def_with_condition_string_field_def_string_type,
+    LatitudeClass => def_type,
+    OrderFieldDef => order_field_def_type,
+    OrderFieldDefClass => def_type,
+    RowColumnEncodingFieldDef => row_column_encoding_field_def_type,
+    StringFieldDef => string_field_def_type,
+    TypedFieldDef => typed_field_def_type,
+    XClass => def_type,
+    YClass => def_type,
+);
+
+impl<T: FillType> FillType for Vec<T> {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        for def in self {
+            def.fill_type(types);
+        }
+    }
+}
+
+impl<T: FillType> FillType for Option<T> {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        if let Some(def) = self {
+            def.fill_type(types);
+        }
+    }
+}
+
+impl<T: FillType + Clone> FillType for RemovableValue<T> {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        if let RemovableValue::Specified(def) = self {
+            def.fill_type(types);
+        }
+    }
+}
+
+impl FillType for Detail {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        match self {
+            Detail::TypedFieldDef(def) => def.fill_type(types),
+            Detail::TypedFieldDefArray(defs) => defs.fill_type(types),
+        }
+    }
+}
+
+impl FillType for Order {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        match self {
+            Order::OrderFieldDefArray(defs) => defs.fill_type(types),
+            Order::OrderFieldDefClass(def) => def.fill_type(types),
+        }
+    }
+}
+
+impl FillType for Tooltip {
+    fn fill_type(&mut self, types: &FieldTypes) {
+        match self {
+            Tooltip::FieldDefWithConditionStringFieldDefString(def) => def.fill_type(types),
+            Tooltip::StringFieldDefArray(defs) => defs.fill_type(types),
+        }
+    }
+}
+
+// for encodings, with their channels that have a type: secondary channels (`x2`, `xError`, ...) take the type of
+// their primary channel
+macro_rules! fill_type_encoding {
+    ( $x:ident : $( $channel:ident ),* $(,)? ) => {
+        impl FillType for $x {
+            fn fill_type(&mut self, types: &FieldTypes) {
+                $(
+                    self.$channel.fill_type(types);
+                )*
+            }
+        }
+    };
+}
+
+fill_type_encoding!(Encoding:
+    color, column, detail, facet, fill, fill_opacity, href, key, latitude, longitude, opacity, order, row, shape,
+    size, stroke, stroke_opacity, stroke_width, text, tooltip, url, x, y,
+);
+fill_type_encoding!(LayerEncoding:
+    color, detail, fill, fill_opacity, href, key, latitude, longitude, opacity, order, shape, size, stroke,
+    stroke_opacity, stroke_width, text, tooltip, url, x, y,
+);
+
+impl Vegalite {
+    /// Fill the missing `type` of the encoding channels, from the inline data of the chart (or of the view):
+    /// * numbers are `quantitative`,
+    /// * dates written as `2020-01-31` (optionally with a time) are `temporal`,
+    /// * anything else, or a mix of types, is `nominal`.
+    ///
+    /// Channels with an `aggregate` are `quantitative` and channels with a `timeUnit` are `temporal`. Channels
+    /// that already have a type, whose field isn't in inline data, or that don't accept the inferred type (like a
+    /// quantitative `shape`), are left untouched.
+    pub fn infer_types_from_inline_data(&mut self) {
+        self.visit_views_with_state_mut(&Rc::new(FieldTypes::new()), &mut |mut view, parent| {
+            // a view with its own data doesn't use the data of its parent
+            let types = match view.data() {
+                RemovableValue::Default => Rc::clone(parent),
+                RemovableValue::Specified(data) => Rc::new(field_types(data)),
+                RemovableValue::Remove => Rc::new(FieldTypes::new()),
+            };
+            match view.encoding() {
+                Some(EncodingMut::Unit(encoding)) => encoding.fill_type(&types),
+                Some(EncodingMut::Layer(encoding)) => encoding.fill_type(&types),
+                None => (),
+            }
+            types
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    fn bar_chart() -> Vegalite {
+        let mut chart = test_charts::bar_chart();
+        chart.data = UrlData::from_json_value(json!([
+            {"a": "A", "b": 28, "c": "2020-01-31"},
+            {"a": "B", "b": 55, "c": "2020-02-29"},
+        ]))
+        .unwrap()
+        .into();
+        chart
+    }
+
+    #[test]
+    fn infer_from_inline_data() {
+        let mut chart = bar_chart();
+        chart.infer_types_from_inline_data();
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(json["encoding"]["x"]["type"], "nominal");
+        assert_eq!(json["encoding"]["y"]["type"], "quantitative");
+    }
+
+    #[test]
+    fn keep_given_type() {
+        let mut chart = bar_chart();
+        chart.encoding_mut().x.as_mut().unwrap().def_type = Some(StandardType::Ordinal);
+        chart.infer_types_from_inline_data();
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(json["encoding"]["x"]["type"], "ordinal");
+    }
+
+    #[test]
+    fn layers_use_their_own_data() {
+        let mut line = LayerSpec::default();
+        line.encoding_mut().x = Some(XClassBuilder::default().field("c").build().unwrap());
+        let mut from_url = line.clone();
+        from_url.data = UrlData::url_with_format("data.csv", DataFormatType::Csv).into();
+        let mut chart = bar_chart();
+        chart.layers_mut().push(line);
+        chart.layers_mut().push(from_url);
+        chart.infer_types_from_inline_data();
+        let json = serde_json::to_value(&chart).unwrap();
+        // the bar is moved to the first layer
+        assert_eq!(json["layer"][0]["encoding"]["x"]["type"], "nominal");
+        assert_eq!(json["layer"][1]["encoding"]["x"]["type"], "temporal");
+        assert!(json["layer"][2]["encoding"]["x"].get("type").is_none());
+    }
+}
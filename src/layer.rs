@@ -0,0 +1,269 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::composition::CompositionKind;
+use crate::data::iter_to_data;
+use crate::schema::*;
+use serde_json::{Map, Value};
+
+/// Position of an annotation along an axis, see
+/// [`Vegalite::add_annotation`](struct.Vegalite.html#method.add_annotation)
+#[derive(Debug, Clone)]
+pub enum AnnotationPos {
+    /// A position in pixels, from the left of the plot for `x` and from its top for `y`
+    Pixel(f64),
+    /// A number, on a quantitative scale
+    Quantitative(f64),
+    /// A date, on a temporal scale
+    Temporal(String),
+    /// A category, on a discrete scale
+    Nominal(String),
+}
+
+impl AnnotationPos {
+    /// The value to put in the annotation data, and its type, if positioned with data
+    fn datum(&self) -> Option<(Value, StandardType)> {
+        match self {
+            AnnotationPos::Pixel(_) => None,
+            AnnotationPos::Quantitative(v) => Some((Value::from(*v), StandardType::Quantitative)),
+            AnnotationPos::Temporal(v) => Some((Value::from(v.as_str()), StandardType::Temporal)),
+            AnnotationPos::Nominal(v) => Some((Value::from(v.as_str()), StandardType::Nominal)),
+        }
+    }
+}
+
+/// Facet channels (`row`, `column` and `facet`) can't be used in a layer and are dropped.
+impl From<Encoding> for LayerEncoding {
+    fn from(e: Encoding) -> Self {
+        LayerEncoding {
+            color: e.color,
+            detail: e.detail,
+            fill: e.fill,
+            fill_opacity: e.fill_opacity,
+            href: e.href,
+            key: e.key,
+            latitude: e.latitude,
+            latitude2: e.latitude2,
+            longitude: e.longitude,
+            longitude2: e.longitude2,
+            opacity: e.opacity,
+            order: e.order,
+            shape: e.shape,
+            size: e.size,
+            stroke: e.stroke,
+            stroke_opacity: e.stroke_opacity,
+            stroke_width: e.stroke_width,
+            text: e.text,
+            tooltip: e.tooltip,
+            url: e.url,
+            x: e.x,
+            x2: e.x2,
+            x_error: e.x_error,
+            x_error2: e.x_error2,
+            y: e.y,
+            y2: e.y2,
+            y_error: e.y_error,
+            y_error2: e.y_error2,
+        }
+    }
+}
+
+// for views that can be turned into layered views
+macro_rules! view_layers_mut {
+    ( $( $x:ident ),* $(,)? ) => {
+        $(
+            impl $x {
+                /// Get the layers of the view. A single view is first turned into a layered view, its mark,
+                /// encoding and selection being moved to the first layer.
+                fn view_layers_mut(&mut self) -> &mut Vec<LayerSpec> {
+                    if self.layer.is_none() && self.mark.is_some() {
+                        let first = LayerSpec {
+                            mark: self.mark.take(),
+                            encoding: self.encoding.take().map(LayerEncoding::from),
+                            selection: self.selection.take(),
+                            ..Default::default()
+                        };
+                        self.layer = Some(vec![first]);
+                    }
+                    self.layer.get_or_insert_with(Vec::new)
+                }
+            }
+        )*
+    };
+}
+
+view_layers_mut!(Vegalite, SpecClass);
+
+impl Vegalite {
+    /// Get the layers of the chart, or of its `spec` if it's faceted or repeated, so that the layers are added to
+    /// every facet or repetition. A single view is first turned into a layered view, its mark, encoding and
+    /// selection being moved to the first layer.
+    ///
+    /// # Panics
+    ///
+    /// If the chart is a concatenation, that has no single view to add layers to.
+    pub(crate) fn layers_mut(&mut self) -> &mut Vec<LayerSpec> {
+        match self.composition_kind() {
+            CompositionKind::Facet | CompositionKind::Repeat => self
+                .spec
+                .get_or_insert_with(Default::default)
+                .view_layers_mut(),
+            CompositionKind::Concat => {
+                panic!(
+                    "can't add a layer to a concatenated chart, add it to the concatenated views"
+                )
+            }
+            CompositionKind::Layer | CompositionKind::Unit => self.view_layers_mut(),
+        }
+    }
+
+    /// Add a layer on top of the chart. A single view chart is first turned into a layered chart. On a faceted
+    /// or repeated chart, the layer is added to the `spec` and shown in every facet or repetition.
+    ///
+    /// # Panics
+    ///
+    /// If the chart is a concatenation, that has no single view to add the layer to.
+    pub fn add_layer(mut self, layer: LayerSpec) -> Self {
+        self.layers_mut().push(layer);
+        self
+    }
+
+    /// Add a rule at the mean of `field`. An `Orientation::Horizontal` rule marks the mean on the y axis, an
+    /// `Orientation::Vertical` one on the x axis. Panics on a concatenated chart, like
+    /// [`add_layer`](#method.add_layer).
+    pub fn add_mean_line(self, field: &str, orientation: Orientation) -> Self {
+        self.add_aggregate_line(field, orientation, NonArgAggregateOp::Mean)
+    }
+
+    /// Add a rule at the median of `field`. An `Orientation::Horizontal` rule marks the median on the y axis, an
+    /// `Orientation::Vertical` one on the x axis. Panics on a concatenated chart, like
+    /// [`add_layer`](#method.add_layer).
+    pub fn add_median_line(self, field: &str, orientation: Orientation) -> Self {
+        self.add_aggregate_line(field, orientation, NonArgAggregateOp::Median)
+    }
+
+    fn add_aggregate_line(
+        self,
+        field: &str,
+        orientation: Orientation,
+        op: NonArgAggregateOp,
+    ) -> Self {
+        let encoding = match orientation {
+            Orientation::Horizontal => LayerEncoding {
+                y: Some(YClass {
+                    field: Some(field.into()),
+                    aggregate: Some(op.into()),
+                    def_type: Some(StandardType::Quantitative),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Orientation::Vertical => LayerEncoding {
+                x: Some(XClass {
+                    field: Some(field.into()),
+                    aggregate: Some(op.into()),
+                    def_type: Some(StandardType::Quantitative),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+        self.add_layer(LayerSpec {
+            mark: Some(Mark::Rule.into()),
+            encoding: Some(encoding),
+            ..Default::default()
+        })
+    }
+
+    /// Add a text annotation at the given position. Each coordinate is either in pixels or in data units, the
+    /// annotation having its own data of one row so that it's displayed once (in every facet or repetition of a
+    /// faceted or repeated chart). Panics on a concatenated chart, like [`add_layer`](#method.add_layer).
+    pub fn add_annotation(self, x: AnnotationPos, y: AnnotationPos, text: &str) -> Self {
+        let mut datum = Map::new();
+        let mut encoding = LayerEncoding::default();
+        if let AnnotationPos::Pixel(px) = x {
+            encoding.x = Some(XClass {
+                value: Some(px.into()),
+                ..Default::default()
+            });
+        }
+        if let Some((value, ty)) = x.datum() {
+            datum.insert("x".to_string(), value);
+            encoding.x = Some(XClass {
+                field: Some("x".into()),
+                def_type: Some(ty),
+                ..Default::default()
+            });
+        }
+        if let AnnotationPos::Pixel(px) = y {
+            encoding.y = Some(YClass {
+                value: Some(px.into()),
+                ..Default::default()
+            });
+        }
+        if let Some((value, ty)) = y.datum() {
+            datum.insert("y".to_string(), value);
+            encoding.y = Some(YClass {
+                field: Some("y".into()),
+                def_type: Some(ty),
+                ..Default::default()
+            });
+        }
+        self.add_layer(LayerSpec {
+            data: iter_to_data(std::iter::once(Value::Object(datum))).into(),
+            mark: Some(AnyMark::MarkDefClass(MarkDefClass {
+                def_type: Some(Mark::Text),
+                text: Some(text.into()),
+                ..Default::default()
+            })),
+            encoding: Some(encoding),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_charts::bar_chart;
+    use crate::*;
+
+    #[test]
+    fn layer_is_added_to_the_view() {
+        let chart = bar_chart().add_mean_line("b", Orientation::Horizontal);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("mark").is_none());
+        assert!(json.get("encoding").is_none());
+        assert_eq!(json["layer"][0]["mark"], "bar");
+        assert_eq!(json["layer"][1]["mark"], "rule");
+    }
+
+    #[test]
+    fn layer_is_added_to_the_faceted_spec() {
+        let chart = bar_chart()
+            .facet_wrap("c", StandardType::Nominal, 3)
+            .add_mean_line("b", Orientation::Horizontal);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("layer").is_none());
+        assert!(json["spec"].get("mark").is_none());
+        assert_eq!(json["spec"]["layer"][0]["mark"], "bar");
+        assert_eq!(json["spec"]["layer"][1]["mark"], "rule");
+    }
+
+    #[test]
+    #[should_panic(expected = "concatenated chart")]
+    fn layer_is_not_added_to_a_concatenation() {
+        let chart = Vegalite {
+            hconcat: Some(vec![Spec::default()]),
+            ..Default::default()
+        };
+        chart.add_mean_line("b", Orientation::Horizontal);
+    }
+}
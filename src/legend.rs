@@ -0,0 +1,55 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+
+impl Legend {
+    /// A legend without title, serialized as `"title": null`. The legend itself is kept.
+    pub fn no_title() -> Self {
+        Legend {
+            title: RemovableValue::Remove,
+            ..Default::default()
+        }
+    }
+
+    /// A compact legend for dense dashboards: placed at the bottom of the chart, without title, with small
+    /// symbols and labels.
+    pub fn compact() -> Self {
+        Legend {
+            orient: Some(LegendOrient::Bottom),
+            symbol_size: Some(40.0),
+            label_font_size: Some(9.0),
+            ..Legend::no_title()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn no_title() {
+        let json = serde_json::to_value(Legend::no_title()).unwrap();
+        assert_eq!(json, json!({"title": null}));
+    }
+
+    #[test]
+    fn compact() {
+        let json = serde_json::to_value(Legend::compact()).unwrap();
+        assert_eq!(
+            json,
+            json!({"title": null, "orient": "bottom", "symbolSize": 40.0, "labelFontSize": 9.0})
+        );
+    }
+}
@@ -34,17 +34,46 @@
     missing_docs
 )]
 
+mod composition;
+mod config;
 mod data;
+mod datetime;
+mod encoding;
+mod export;
+mod gradient;
+mod html;
+mod infer;
+mod layer;
+mod legend;
+mod mark;
+mod merge;
 mod removable_value;
+mod scale;
 mod schema;
+#[cfg(test)]
+mod schema_keys;
+mod selection;
+mod set;
+mod size;
 mod string;
+#[cfg(test)]
+mod test_charts;
+mod transform;
+mod validate;
+mod view;
 
 #[cfg(feature = "show_vega")]
 mod show_vega;
 #[cfg(feature = "show_vega")]
 pub use showata::Showable;
 
+pub use composition::CompositionKind;
+pub use config::{TitleConfig, TitleConfigBuilder};
 pub use data::*;
+pub use html::{EmbedOptions, EmbedOptionsBuilder};
+pub use layer::AnnotationPos;
 pub use removable_value::RemovableValue;
 pub use schema::*;
+pub use set::IntoField;
 pub use string::*;
+pub use validate::ValidationIssue;
@@ -0,0 +1,216 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::composition::CompositionKind;
+use crate::schema::*;
+
+impl AnyMark {
+    /// Get the mark definition, turning a bare mark (`"line"`) into a definition of the same type
+    /// (`{"type": "line"}`) first if needed.
+    pub fn as_def_mut(&mut self) -> &mut MarkDefClass {
+        if let AnyMark::Enum(mark) = self {
+            let mark = mark.clone();
+            *self = AnyMark::MarkDefClass(MarkDefClass {
+                def_type: Some(mark),
+                ..Default::default()
+            });
+        }
+        match self {
+            AnyMark::MarkDefClass(def) => def,
+            AnyMark::Enum(_) => {
+                unreachable!("bare mark has just been upgraded to a mark definition")
+            }
+        }
+    }
+
+    /// Apply the named style `name`, defined with [`Config::add_style`](struct.Config.html#method.add_style), to
+    /// the mark. A bare mark is turned into a definition of the same type.
+    pub fn style(mut self, name: &str) -> Self {
+        self.as_def_mut().style = Some(name.into());
+        self
+    }
+}
+
+// the mark definitions of layers, looking into the nested layers of the layers without a mark
+fn layer_mark_defs<'a>(layers: &'a mut [LayerSpec], defs: &mut Vec<&'a mut MarkDefClass>) {
+    for LayerSpec { mark, layer, .. } in layers {
+        match (mark, layer) {
+            (Some(mark), _) => defs.push(mark.as_def_mut()),
+            (None, Some(layers)) => layer_mark_defs(layers, defs),
+            (None, None) => (),
+        }
+    }
+}
+
+// for views that have a mark or layers
+macro_rules! view_mark_defs_mut {
+    ( $( $x:ident ),* $(,)? ) => {
+        $(
+            impl $x {
+                /// Get the mark definitions of the view: the ones of its layers if it's layered, or its own mark,
+                /// set to `default` if there is no mark yet.
+                fn view_mark_defs_mut(&mut self, default: Option<Mark>) -> Vec<&mut MarkDefClass> {
+                    let mut defs = Vec::new();
+                    match (&mut self.mark, &mut self.layer) {
+                        (_, Some(layers)) => layer_mark_defs(layers, &mut defs),
+                        (mark, None) => {
+                            if mark.is_none() {
+                                *mark = default.map(AnyMark::Enum);
+                            }
+                            if let Some(mark) = mark {
+                                defs.push(mark.as_def_mut());
+                            }
+                        }
+                    }
+                    defs
+                }
+            }
+        )*
+    };
+}
+
+view_mark_defs_mut!(Vegalite, SpecClass);
+
+impl Vegalite {
+    /// Get the mark definitions of the chart, or of its `spec` if it's faceted or repeated: the ones of every layer
+    /// of a layered chart, or the mark of a single view, set to `default` if there is no mark yet.
+    ///
+    /// # Panics
+    ///
+    /// If the chart is a concatenation, whose marks are in the concatenated views.
+    fn mark_defs_mut(&mut self, default: Option<Mark>) -> Vec<&mut MarkDefClass> {
+        match self.composition_kind() {
+            CompositionKind::Facet | CompositionKind::Repeat => self
+                .spec
+                .get_or_insert_with(Default::default)
+                .view_mark_defs_mut(default),
+            CompositionKind::Concat => {
+                panic!(
+                    "can't change the mark of a concatenated chart, change the concatenated views"
+                )
+            }
+            CompositionKind::Layer | CompositionKind::Unit => self.view_mark_defs_mut(default),
+        }
+    }
+
+    /// Set the interpolation method of the line (for example `Interpolate::Step` or
+    /// `Interpolate::Monotone`). The chart is given a line mark if it doesn't have a mark yet, the marks of every
+    /// layer of a layered chart are changed. Panics on a concatenated chart.
+    pub fn mark_line_interpolate(mut self, interpolate: Interpolate) -> Self {
+        for def in self.mark_defs_mut(Some(Mark::Line)) {
+            def.interpolate = Some(interpolate.clone());
+        }
+        self
+    }
+
+    /// Fill the area with a vertical linear gradient, from `top_color` at the top to `bottom_color` at the
+    /// bottom. The chart is given an area mark if it doesn't have a mark yet, the marks of every layer of a layered
+    /// chart are changed. Panics on a concatenated chart.
+    pub fn mark_area_gradient(mut self, top_color: &str, bottom_color: &str) -> Self {
+        let stops = vec![
+            GradientStop::new(0.0, bottom_color),
+            GradientStop::new(1.0, top_color),
+        ];
+        let gradient = ColorLinearGradient {
+            x1: Some(1.0),
+            x2: Some(1.0),
+            y1: Some(1.0),
+            y2: Some(0.0),
+            ..ColorLinearGradient::new(stops).expect("stops at 0 and 1 are valid")
+        };
+        for def in self.mark_defs_mut(Some(Mark::Area)) {
+            def.color = Some(gradient.clone().into());
+        }
+        self
+    }
+
+    /// Clip the marks to the plot area, for example large points near the edges or marks outside of a zoomed
+    /// scale domain. The marks of every layer of a layered chart are clipped.
+    ///
+    /// # Panics
+    ///
+    /// If the chart has no mark yet, as clipping doesn't tell which mark to use: set it first. Also on a
+    /// concatenated chart.
+    pub fn mark_clip(mut self, clip: bool) -> Self {
+        let defs = self.mark_defs_mut(None);
+        assert!(
+            !defs.is_empty(),
+            "can't clip a chart without mark, set its mark first"
+        );
+        for def in defs {
+            def.clip = Some(clip);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_charts::bar_chart;
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn mark_area_gradient() {
+        let chart = Vegalite::default().mark_area_gradient("darkgreen", "white");
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(
+            json["mark"],
+            json!({
+                "type": "area",
+                "color": {
+                    "gradient": "linear",
+                    "stops": [
+                        {"offset": 0.0, "color": "white"},
+                        {"offset": 1.0, "color": "darkgreen"},
+                    ],
+                    "x1": 1.0,
+                    "x2": 1.0,
+                    "y1": 1.0,
+                    "y2": 0.0,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn mark_clip() {
+        let chart = bar_chart().mark_clip(true);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(json["mark"], json!({"type": "bar", "clip": true}));
+    }
+
+    #[test]
+    #[should_panic(expected = "without mark")]
+    fn mark_clip_without_mark() {
+        Vegalite::default().mark_clip(true);
+    }
+
+    #[test]
+    fn layers_are_changed() {
+        let mut chart = bar_chart();
+        chart.layers_mut().push(LayerSpec {
+            mark: Some(Mark::Rule.into()),
+            ..Default::default()
+        });
+        let chart = chart.mark_clip(true);
+        let json = serde_json::to_value(&chart).unwrap();
+        assert!(json.get("mark").is_none());
+        assert_eq!(
+            json["layer"][0]["mark"],
+            json!({"type": "bar", "clip": true})
+        );
+        assert_eq!(
+            json["layer"][1]["mark"],
+            json!({"type": "rule", "clip": true})
+        );
+    }
+}
@@ -0,0 +1,347 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Recursively merge `overlay` into `base`: objects are merged key by key, any other value from `overlay`
+/// replaces the one from `base`.
+pub(crate) fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// A value that an overlay of the same type can be merged into
+pub(crate) trait Merge {
+    /// Merge `overlay` into `self`, following the rules of [`Vegalite::merge`](struct.Vegalite.html#method.merge).
+    /// The overlay is taken by reference, not to copy the (large) views around.
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error>;
+}
+
+// merge through json, only when both values are objects, otherwise `overlay` replaces `base` without a
+// round-trip
+fn merge_json<T>(base: &mut T, overlay: &T) -> Result<(), serde_json::Error>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let overlay_json = serde_json::to_value(overlay)?;
+    let mut merged = serde_json::to_value(&*base)?;
+    if !(merged.is_object() && overlay_json.is_object()) {
+        *base = overlay.clone();
+        return Ok(());
+    }
+    merge_values(&mut merged, overlay_json);
+    *base = serde_json::from_value(merged)?;
+    Ok(())
+}
+
+impl<T: Merge + Clone> Merge for Option<T> {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        match (self.as_mut(), overlay) {
+            (_, None) => Ok(()),
+            (Some(base), Some(overlay)) => base.merge(overlay),
+            (None, overlay) => {
+                *self = overlay.clone();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Merge + Clone> Merge for RemovableValue<T> {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        match (&mut *self, overlay) {
+            (_, RemovableValue::Default) => Ok(()),
+            (RemovableValue::Specified(base), RemovableValue::Specified(overlay)) => {
+                base.merge(overlay)
+            }
+            (_, overlay) => {
+                *self = overlay.clone();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Merge> Merge for Box<T> {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        (**self).merge(overlay)
+    }
+}
+
+// lists are replaced, not appended
+impl<T: Clone> Merge for Vec<T> {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        *self = overlay.clone();
+        Ok(())
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Merge + Clone> Merge for HashMap<K, V> {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        for (key, value) in overlay {
+            match self.get_mut(key) {
+                Some(existing) => existing.merge(value)?,
+                None => {
+                    self.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// for values that are replaced by the overlay
+macro_rules! merge_replace {
+    ( $( $x:ty ),* $(,)? ) => {
+        $(
+            impl Merge for $x {
+                fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+                    *self = overlay.clone();
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+merge_replace!(String, f64);
+
+impl Merge for Value {
+    fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+        merge_values(self, overlay.clone());
+        Ok(())
+    }
+}
+
+// for values merged through their json
+macro_rules! merge_json {
+    ( $( $x:ty ),* $(,)? ) => {
+        $(
+            impl Merge for $x {
+                fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+                    merge_json(self, overlay)
+                }
+            }
+        )*
+    };
+}
+
+merge_json!(
+    AlignUnion,
+    AnyMark,
+    Autosize,
+    BoundsEnum,
+    Facet,
+    HeightUnion,
+    InlineDatasetValue,
+    Padding,
+    Projection,
+    RepeatUnion,
+    Resolve,
+    SelectionDef,
+    Spacing,
+    Text,
+    UrlData,
+    VegaliteCenter,
+    ViewBackground,
+);
+// channels
+merge_json!(
+    DefWithConditionMarkPropFieldDefGradientStringNull,
+    DefWithConditionMarkPropFieldDefNumber,
+    DefWithConditionMarkPropFieldDefTypeForShapeStringNull,
+    DefWithConditionStringFieldDefText,
+    Detail,
+    FacetEncodingFieldDef,
+    HrefClass,
+    Latitude2Class,
+    LatitudeClass,
+    Order,
+    RowColumnEncodingFieldDef,
+    Tooltip,
+    TypedFieldDef,
+    X2Class,
+    XClass,
+    Y2Class,
+    YClass,
+);
+// configs
+merge_json!(
+    AreaConfig,
+    AxisConfig,
+    BaseMarkConfig,
+    BoxPlotConfig,
+    CompositionConfig,
+    ErrorBandConfig,
+    ErrorBarConfig,
+    ExcludeMappedValueRefBaseTitle,
+    FieldTitle,
+    HeaderConfig,
+    LegendConfig,
+    LineConfig,
+    MarkConfig,
+    RangeConfig,
+    RectConfig,
+    ScaleConfig,
+    SelectionConfig,
+    TickConfig,
+    ViewConfig,
+);
+
+// for views, encodings and configs, merged field by field: deserializing them whole takes a lot of stack in
+// debug builds, and the nested views are never deserialized
+macro_rules! merge_fields {
+    ( $x:ident : $( $field:ident ),* $(,)? ) => {
+        impl Merge for $x {
+            fn merge(&mut self, overlay: &Self) -> Result<(), serde_json::Error> {
+                $(
+                    self.$field.merge(&overlay.$field)?;
+                )*
+                Ok(())
+            }
+        }
+    };
+}
+
+merge_fields!(Vegalite:
+    schema, autosize, background, bounds, config, data, datasets, description, encoding, height, mark, name,
+    padding, projection, resolve, selection, title, transform, usermeta, view, width, align, center, columns, facet,
+    spacing, spec, layer, repeat, concat, vconcat, hconcat,
+);
+merge_fields!(SpecClass:
+    data, description, encoding, height, layer, name, projection, resolve, title, transform, view, width, bounds,
+    mark, selection, align, center, columns, facet, spacing, spec, repeat, concat, vconcat, hconcat,
+);
+merge_fields!(Encoding:
+    color, column, detail, facet, fill, fill_opacity, href, key, latitude, latitude2, longitude, longitude2,
+    opacity, order, row, shape, size, stroke, stroke_opacity, stroke_width, text, tooltip, url, x, x2, x_error,
+    x_error2, y, y2, y_error, y_error2,
+);
+merge_fields!(Config:
+    area, autosize, axis, axis_band, axis_bottom, axis_left, axis_right, axis_top, axis_x, axis_y, background, bar,
+    boxplot, circle, concat, count_title, errorband, errorbar, facet, field_title, geoshape, header, header_column,
+    header_facet, header_row, image, legend, line, mark, number_format, padding, point, projection, range, rect,
+    repeat, rule, scale, selection, square, style, text, tick, time_format, title, trail, view,
+);
+
+impl Vegalite {
+    /// Merge `overlay` on top of this chart, to apply a fragment (a house config, common transforms, ...) to a
+    /// base chart.
+    ///
+    /// The merge is done field by field, recursively:
+    /// * a field not set in `overlay` keeps the value from this chart,
+    /// * a field set in `overlay` to an object is merged with the same field of this chart if it's also an object,
+    /// * any other field set in `overlay` replaces the value from this chart. This includes lists (`layer`,
+    ///   `transform`, `vconcat`, ...) that are replaced and not appended, and fields explicitly removed with
+    ///   `RemovableValue::Remove` that stay removed.
+    pub fn merge(mut self, overlay: Vegalite) -> Result<Vegalite, serde_json::Error> {
+        Merge::merge(&mut self, &overlay)?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Merge;
+    use crate::*;
+    use serde_json::{json, Value};
+
+    fn base() -> Vegalite {
+        let mut chart = test_charts::bar_chart();
+        chart.title = Some("base".into());
+        chart.data = UrlData::url_with_format("data.csv", DataFormatType::Csv).into();
+        chart.transform = Some(vec![Transform::sample(10)]);
+        chart.config = Some(Config::default().no_view_border());
+        chart
+    }
+
+    fn merged(overlay: &Vegalite) -> Value {
+        let mut chart = base();
+        Merge::merge(&mut chart, overlay).unwrap();
+        serde_json::to_value(&chart).unwrap()
+    }
+
+    #[test]
+    fn unset_fields_are_kept() {
+        let chart = base().merge(Vegalite::default()).unwrap();
+        let json = serde_json::to_value(&chart).unwrap();
+        assert_eq!(json, serde_json::to_value(base()).unwrap());
+    }
+
+    #[test]
+    fn objects_are_merged() {
+        let mut overlay = Vegalite {
+            config: Some(Config {
+                background: Some("white".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        overlay.encoding_mut().x = Some(XClass {
+            def_type: Some(StandardType::Ordinal),
+            ..Default::default()
+        });
+        let json = merged(&overlay);
+        assert_eq!(
+            json["encoding"]["x"],
+            json!({"field": "a", "type": "ordinal"})
+        );
+        assert_eq!(json["encoding"]["y"], json!({"field": "b"}));
+        assert_eq!(
+            json["config"],
+            json!({"view": {"stroke": null}, "background": "white"})
+        );
+        assert_eq!(json["title"], "base");
+    }
+
+    #[test]
+    fn lists_are_replaced() {
+        let overlay = Vegalite {
+            transform: Some(vec![Transform::sample(20)]),
+            ..Default::default()
+        };
+        let json = merged(&overlay);
+        assert_eq!(json["transform"], json!([{"sample": 20.0}]));
+    }
+
+    #[test]
+    fn removed_fields_stay_removed() {
+        let mut overlay = Vegalite {
+            data: RemovableValue::Remove,
+            ..Default::default()
+        };
+        overlay.encoding_mut().y = Some(YClass {
+            axis: RemovableValue::Remove,
+            ..Default::default()
+        });
+        let json = merged(&overlay);
+        assert_eq!(json["data"], Value::Null);
+        assert!(json.as_object().unwrap().contains_key("data"));
+        assert_eq!(json["encoding"]["y"], json!({"field": "b", "axis": null}));
+    }
+}
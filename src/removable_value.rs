@@ -18,6 +18,31 @@ use std::marker::PhantomData;
 use crate::schema::*;
 
 /// Wrapper for a field that can be either the default value, null or specified
+///
+/// Vega-Lite gives a different meaning to a missing field and to a field set to `null`: a missing `title` gets the
+/// default title, a `null` one removes it. The three states of `RemovableValue` are serialized as:
+/// * `Default`: the field is omitted, and Vega-Lite uses its default,
+/// * `Remove`: the field is `null`, to remove what Vega-Lite would show by default,
+/// * `Specified(value)`: the field is `value`.
+///
+/// Deserializing gives back the same state: a missing field is `Default`, a `null` field is `Remove`.
+///
+/// ```
+/// use vega_lite_4::*;
+///
+/// let axis = Axis {
+///     title: RemovableValue::Remove,
+///     ..Default::default()
+/// };
+/// assert_eq!(serde_json::to_string(&axis).unwrap(), r#"{"title":null}"#);
+/// assert_eq!(serde_json::to_string(&Axis::default()).unwrap(), "{}");
+///
+/// let axis: Axis = serde_json::from_str(r#"{"title":null}"#).unwrap();
+/// assert!(Option::<PurpleText>::from(axis.title).is_none());
+/// ```
+///
+/// A value converts into a `Specified` one with `into()`, and a `RemovableValue` into an `Option` that is `None`
+/// for both `Default` and `Remove`.
 #[derive(Clone, Debug)]
 pub enum RemovableValue<T: Clone> {
     /// The default value for this field
@@ -46,6 +71,21 @@ impl<T: Clone> RemovableValue<T> {
             _ => false,
         }
     }
+
+    /// Get the specified value, replacing a `Default` or `Remove` value with the default of `T` first
+    pub(crate) fn get_or_insert_default(&mut self) -> &mut T
+    where
+        T: Default,
+    {
+        if let RemovableValue::Specified(value) = self {
+            return value;
+        }
+        *self = RemovableValue::Specified(T::default());
+        match self {
+            RemovableValue::Specified(value) => value,
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl<T: Clone> From<T> for RemovableValue<T> {
@@ -54,6 +94,15 @@ impl<T: Clone> From<T> for RemovableValue<T> {
     }
 }
 
+impl<T: Clone> From<RemovableValue<T>> for Option<T> {
+    fn from(value: RemovableValue<T>) -> Self {
+        match value {
+            RemovableValue::Specified(value) => Some(value),
+            RemovableValue::Default | RemovableValue::Remove => None,
+        }
+    }
+}
+
 macro_rules! from_into_with_removable{
     ( $( $from:ty => $to:ty ),* $(,)? ) => {
             $(
@@ -0,0 +1,51 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+impl Scale {
+    /// A `band` scale, dividing the range into one band per category, as used by bars and rects.
+    ///
+    /// `padding_inner` is the fraction of each step left empty between bands, between `0` and `1`. It defaults to
+    /// `config.scale.barBandPaddingInner` for bars (`0.1`) and `config.scale.rectBandPaddingInner` for other marks
+    /// (`0`).
+    pub fn band(padding_inner: Option<f64>) -> Self {
+        Scale {
+            scale_type: Some(ScaleType::Band),
+            padding_inner,
+            ..Default::default()
+        }
+    }
+
+    /// A `point` scale, placing one point per category, as used by points and lines. Unlike a `band` scale, there
+    /// is no empty space between the categories.
+    ///
+    /// `padding` is the space left at both ends of the range, as a fraction of the step. It defaults to
+    /// `config.scale.pointPadding` (`0.5` in Vega-Lite).
+    pub fn point(padding: Option<f64>) -> Self {
+        Scale {
+            scale_type: Some(ScaleType::Point),
+            padding,
+            ..Default::default()
+        }
+    }
+
+    /// A scale with a fixed temporal domain, from `start` to `end`.
+    pub fn domain_datetime(start: DateTime, end: DateTime) -> Self {
+        Scale {
+            domain: Some(DomainUnion::UnionArray(vec![
+                Equal::DateTime(start),
+                Equal::DateTime(end),
+            ])),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,313 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Check the json keys of every struct of the schema against `schema_keys.golden`, so that a typo in a
+// `#[serde(rename = "...")]` is caught. The keys are the field names serde knows for each struct, after renaming,
+// which are also the keys of a fully populated instance once serialized.
+//
+// After regenerating the schema, review the diff of the golden file written by:
+// `UPDATE_GOLDEN=1 cargo test schema_keys`
+
+use crate::schema::*;
+use serde::de::{self, Deserialize, Visitor};
+use serde::forward_to_deserialize_any;
+use std::collections::BTreeMap;
+use std::fmt;
+
+const GOLDEN_PATH: &str = "src/schema_keys.golden";
+
+#[derive(Debug)]
+enum Probe {
+    Fields(&'static [&'static str]),
+    Other(String),
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Probe::Fields(fields) => write!(f, "fields {:?}", fields),
+            Probe::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Probe {}
+
+impl de::Error for Probe {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Probe::Other(msg.to_string())
+    }
+}
+
+// a deserializer that stops at the first struct, giving back the names of its fields
+struct FieldNames;
+
+impl<'de> de::Deserializer<'de> for FieldNames {
+    type Error = Probe;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probe> {
+        Err(Probe::Other("not a struct".to_string()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Probe> {
+        Err(Probe::Fields(fields))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+fn keys_of<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+    match T::deserialize(FieldNames) {
+        Err(Probe::Fields(fields)) => {
+            let mut keys = fields.to_vec();
+            keys.sort_unstable();
+            keys
+        }
+        Err(Probe::Other(msg)) => panic!("not a struct: {}", msg),
+        Ok(_) => panic!("not a struct"),
+    }
+}
+
+macro_rules! schema_keys {
+    ( $( $x:ident ),* $(,)? ) => {
+        fn schema_keys() -> BTreeMap<&'static str, Vec<&'static str>> {
+            let mut keys = BTreeMap::new();
+            $(
+                keys.insert(stringify!($x), keys_of::<$x>());
+            )*
+            keys
+        }
+    };
+}
+
+schema_keys!(
+    Vegalite,
+    RowColLayoutAlign,
+    AutoSizeParams,
+    RowColBoolean,
+    SpecClass,
+    Spec,
+    UrlData,
+    DataFormat,
+    GraticuleParams,
+    SequenceParams,
+    SphereClass,
+    Encoding,
+    DefWithConditionMarkPropFieldDefGradientStringNull,
+    ArgmDef,
+    BinParams,
+    BinExtentClass,
+    ConditionalValueDefGradientStringNull,
+    Selection,
+    Predicate,
+    DateTime,
+    ValueLinearGradient,
+    GradientStop,
+    ConditionalPredicateValueDefGradientStringNullClass,
+    RepeatRef,
+    Legend,
+    Scale,
+    DomainClass,
+    ScaleInterpolateParams,
+    NiceClass,
+    SchemeParams,
+    EncodingSortField,
+    RowColumnEncodingFieldDef,
+    Header,
+    SortEncodingSortField,
+    TypedFieldDef,
+    FacetEncodingFieldDef,
+    RowColNumber,
+    DefWithConditionMarkPropFieldDefNumber,
+    ConditionalNumberValueDef,
+    ConditionalDef,
+    HrefClass,
+    ConditionElement,
+    ConditionalPredicateValueDefStringClass,
+    LatitudeClass,
+    Latitude2Class,
+    OrderFieldDef,
+    OrderFieldDefClass,
+    DefWithConditionMarkPropFieldDefTypeForShapeStringNull,
+    ConditionalStringValueDef,
+    ConditionalPredicateMarkPropFieldDefTypeForShapeClass,
+    DefWithConditionStringFieldDefText,
+    ConditionalValueDefText,
+    ConditionalPredicateValueDefTextClass,
+    StringFieldDef,
+    FieldDefWithConditionStringFieldDefString,
+    XClass,
+    Axis,
+    ConditionalAxisPropertyColorNull,
+    ConditionalPredicateValueDefColorNull,
+    ConditionalAxisPropertyNumberNull,
+    ConditionalPredicateValueDefNumberNull,
+    ConditionalAxisPropertyNumberNullClass,
+    ConditionalPredicateValueDefNumberNullElement,
+    ConditionalAxisPropertyTextBaselineNull,
+    ConditionalPredicateValueDefTextBaselineNull,
+    ConditionalAxisPropertyStringNull,
+    ConditionalPredicateStringValueDef,
+    ConditionalAxisPropertyFontStyleNull,
+    ConditionalPredicateValueDefFontStyleNull,
+    ConditionalAxisPropertyFontWeightNull,
+    ConditionalPredicateValueDefFontWeightNull,
+    ImputeParams,
+    ImputeSequence,
+    X2Class,
+    YClass,
+    Y2Class,
+    Facet,
+    FacetFieldDef,
+    Step,
+    LayerSpec,
+    LayerEncoding,
+    MarkDefClass,
+    MarkConfig,
+    ColorLinearGradient,
+    FillLinearGradient,
+    TooltipContent,
+    OverlayMarkDef,
+    Projection,
+    Resolve,
+    AxisResolveMap,
+    LegendResolveMap,
+    ScaleResolveMap,
+    SelectionDef,
+    PurpleBinding,
+    Stream,
+    ClearDerivedStream,
+    BrushConfig,
+    OnDerivedStream,
+    TitleParams,
+    Transform,
+    AggregatedFieldDef,
+    Lookup,
+    Data,
+    JoinAggregateFieldDef,
+    SortField,
+    WindowFieldDef,
+    ViewBackground,
+    RepeatMapping,
+    Config,
+    AreaConfig,
+    AxisConfig,
+    RectConfig,
+    BoxPlotConfig,
+    CompositionConfig,
+    ErrorBandConfig,
+    ErrorBarConfig,
+    HeaderConfig,
+    LegendConfig,
+    LegendLayout,
+    PurpleSignalRef,
+    BaseLegendLayout,
+    LineConfig,
+    PaddingClass,
+    RangeConfig,
+    CategorySignalRef,
+    DivergingSignalRef,
+    HeatmapSignalRef,
+    OrdinalSignalRef,
+    RampSignalRef,
+    ScaleConfig,
+    SelectionConfig,
+    IntervalSelectionConfig,
+    MultiSelectionConfig,
+    LegendStreamBinding,
+    SingleSelectionConfig,
+    FluffyBinding,
+    BaseMarkConfig,
+    TickConfig,
+    ExcludeMappedValueRefBaseTitle,
+    ViewConfig,
+    DiscreteHeightClass,
+    DiscreteWidthClass,
+);
+
+// one line per struct: `Struct: key1 key2 ...`, keys sorted
+fn render(keys: &BTreeMap<&'static str, Vec<&'static str>>) -> String {
+    keys.iter()
+        .map(|(name, keys)| format!("{}: {}\n", name, keys.join(" ")))
+        .collect()
+}
+
+fn parse(golden: &str) -> BTreeMap<&str, Vec<&str>> {
+    golden
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut parts = line.splitn(2, ':');
+            let name = parts.next().unwrap().trim();
+            let keys = parts.next().unwrap_or("").split_whitespace().collect();
+            (name, keys)
+        })
+        .collect()
+}
+
+#[test]
+fn schema_keys_match_golden() {
+    let actual = schema_keys();
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_PATH);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, render(&actual)).unwrap();
+        return;
+    }
+    let golden = std::fs::read_to_string(&path).unwrap();
+    let expected = parse(&golden);
+
+    let mut diffs = Vec::new();
+    for (name, keys) in &expected {
+        match actual.get(name) {
+            None => diffs.push(format!("{}: struct is missing", name)),
+            Some(actual_keys) => {
+                for key in keys.iter().filter(|key| !actual_keys.contains(key)) {
+                    diffs.push(format!("{}: missing key `{}`", name, key));
+                }
+                for key in actual_keys.iter().filter(|key| !keys.contains(key)) {
+                    diffs.push(format!("{}: unexpected key `{}`", name, key));
+                }
+            }
+        }
+    }
+    for name in actual.keys().filter(|name| !expected.contains_key(*name)) {
+        diffs.push(format!("{}: struct is not in {}", name, GOLDEN_PATH));
+    }
+    assert!(
+        diffs.is_empty(),
+        "schema keys differ from {}:\n{}",
+        GOLDEN_PATH,
+        diffs.join("\n")
+    );
+}
+
+#[test]
+fn keys_are_renamed() {
+    let keys = schema_keys();
+    assert!(keys["MarkDefClass"].contains(&"fillOpacity"));
+    assert!(keys["MarkDefClass"].contains(&"strokeOpacity"));
+    assert!(keys["Axis"].contains(&"labelExpr"));
+    assert!(keys["Vegalite"].contains(&"$schema"));
+    for (name, keys) in &keys {
+        for key in keys {
+            assert!(!key.contains('_'), "{}: `{}` is not renamed", name, key);
+        }
+    }
+}
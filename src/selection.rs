@@ -0,0 +1,62 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+impl Vegalite {
+    /// Add a selection named `name` to the chart.
+    pub fn add_selection(mut self, name: &str, selection: SelectionDef) -> Self {
+        self.selection
+            .get_or_insert_with(Default::default)
+            .insert(name.to_string(), selection);
+        self
+    }
+
+    /// Add a selection named `name` of the points clicked, projected over `fields`: clicking a point selects every
+    /// point sharing its values for those fields, and shift-clicking adds to the selection. Used in conditional
+    /// encodings, it highlights the selected combinations of `fields`.
+    pub fn point_on_fields(self, name: &str, fields: &[&str]) -> Self {
+        self.add_selection(
+            name,
+            SelectionDef {
+                selection_def_type: Some(SelectionDefType::Multi),
+                fields: Some(fields.iter().map(|f| f.to_string()).collect()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Make the chart zoomable with the mouse wheel and pannable by dragging, on the `x` axis, the `y` axis or
+    /// both. This adds an interval selection named `zoom`, bound to the scales of the requested axes. Zooming on a
+    /// single axis is handy for time series, where only the time should move. Nothing is added if both are
+    /// `false`.
+    pub fn zoomable(self, bound_x: bool, bound_y: bool) -> Self {
+        let mut encodings = Vec::new();
+        if bound_x {
+            encodings.push(SingleDefUnitChannel::X);
+        }
+        if bound_y {
+            encodings.push(SingleDefUnitChannel::Y);
+        }
+        if encodings.is_empty() {
+            return self;
+        }
+        self.add_selection(
+            "zoom",
+            SelectionDef {
+                selection_def_type: Some(SelectionDefType::Interval),
+                bind: Some(BindUnion::Enum(PurpleLegendBinding::Scales)),
+                encodings: Some(encodings),
+                ..Default::default()
+            },
+        )
+    }
+}
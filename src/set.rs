@@ -0,0 +1,71 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+
+/// Conversion of a value into an optional field, wrapping it into the union type of the field if needed. Used by
+/// the [`set!`](macro.set.html) macro.
+pub trait IntoField<F> {
+    /// Wrap the value into the field type
+    fn into_field(self) -> F;
+}
+
+impl<T, V> IntoField<Option<T>> for V
+where
+    V: Into<T>,
+{
+    fn into_field(self) -> Option<T> {
+        Some(self.into())
+    }
+}
+
+impl<T, V> IntoField<RemovableValue<T>> for V
+where
+    T: Clone,
+    V: Into<T>,
+{
+    fn into_field(self) -> RemovableValue<T> {
+        RemovableValue::Specified(self.into())
+    }
+}
+
+/// Build a struct from its default value, setting the listed fields. Each value is wrapped into the `Option` or
+/// `RemovableValue` of the field, and converted into the union type of the field if needed, so that `x` is the
+/// same as `same_x`:
+/// ```
+/// use vega_lite_4::*;
+///
+/// let x = set!(XClass {
+///     field: "date",
+///     def_type: StandardType::Temporal,
+///     title: "Date",
+/// });
+/// let same_x = XClass {
+///     field: Some(Field::String("date".to_string())),
+///     def_type: Some(StandardType::Temporal),
+///     title: RemovableValue::Specified(PurpleText::String("Date".to_string())),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     serde_json::to_value(&x).unwrap(),
+///     serde_json::to_value(&same_x).unwrap()
+/// );
+/// ```
+/// Fields that are neither an `Option` nor a `RemovableValue` can't be set with this macro.
+#[macro_export]
+macro_rules! set {
+    ( $t:path { $( $field:ident : $value:expr ),* $(,)? } ) => {{
+        #[allow(clippy::field_reassign_with_default)]
+        let mut value = <$t>::default();
+        $( value.$field = $crate::IntoField::into_field($value); )*
+        value
+    }};
+}
@@ -27,36 +27,7 @@ impl Showable for Vegalite {
         })
     }
 
-    // TODO for html use [vega/vega-embed: Publish Vega visualizations as embedded web components with interactive parameters.](https://github.com/vega/vega-embed)
-    // TODO add an Config parameter (with config for to json str, config for embed)
     fn to_html_page(&self) -> Result<String, Error> {
-        let dod = self.to_content_info()?;
-        let content = VEGA_EMBED_HTML_TEMPLATE.replace("{{ spec_as_json }}", &dod.content);
-        Ok(content)
+        Ok(self.to_html()?)
     }
 }
-
-const VEGA_EMBED_HTML_TEMPLATE: &str = r#"
-<!DOCTYPE html>
-<html lang="en">
-<head>
-  <meta charset="utf-8">
-  <!-- Import Vega 5 & Vega-Lite 3 (does not have to be from CDN) -->
-  <script src="https://cdn.jsdelivr.net/npm/vega@5"></script>
-  <script src="https://cdn.jsdelivr.net/npm/vega-lite@3"></script>
-  <!-- Import vega-embed -->
-  <script src="https://cdn.jsdelivr.net/npm/vega-embed@4"></script>
-</head>
-<body>
-
-<div id="vis"></div>
-
-<script type="text/javascript">
-  var spec = {{ spec_as_json }};
-  vegaEmbed('#vis', spec).then(function(result) {
-    // Access the Vega view instance (https://vega.github.io/vega/docs/api/view/) as result.view
-  }).catch(console.error);
-</script>
-</body>
-</html>
-"#;
@@ -0,0 +1,61 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+
+impl HeightUnion {
+    /// A size given by the width (or height) of each discrete step, serialized as `{"step": step}`.
+    pub fn step(step: f64) -> Self {
+        HeightUnion::Step(Step { step: Some(step) })
+    }
+}
+
+impl VegaliteBuilder {
+    /// Set the width of each discrete step of the x axis, so that bands keep the same width whatever the number of
+    /// categories.
+    pub fn width_step(&mut self, step: f64) -> &mut Self {
+        self.width(HeightUnion::step(step))
+    }
+
+    /// Set the height of each discrete step of the y axis, so that bands keep the same height whatever the number
+    /// of categories.
+    pub fn height_step(&mut self, step: f64) -> &mut Self {
+        self.height(HeightUnion::step(step))
+    }
+}
+
+// Vega-Lite default of `config.view.continuousWidth` and `config.view.continuousHeight`
+const DEFAULT_CONTINUOUS_SIZE: f64 = 200.0;
+
+fn resolve_size(size: &Option<HeightUnion>, default: Option<f64>) -> Option<f64> {
+    match size {
+        Some(HeightUnion::Double(size)) => Some(*size),
+        // depends on the page or on the number of categories
+        Some(HeightUnion::Enum(_)) | Some(HeightUnion::Step(_)) => None,
+        None => Some(default.unwrap_or(DEFAULT_CONTINUOUS_SIZE)),
+    }
+}
+
+impl Vegalite {
+    /// The width and height of the plot area of the chart, in pixels. When they are not set, they come from
+    /// `config.view.continuousWidth` and `config.view.continuousHeight`, or from the Vega-Lite default of `200`.
+    ///
+    /// A size is `None` when it can't be known from the spec: `"container"` sizes depend on the page, and step
+    /// sizes on the number of categories. The estimation assumes continuous fields for unset sizes, discrete fields
+    /// use `config.view.discreteWidth` and `discreteHeight` instead.
+    pub fn logical_size(&self) -> (Option<f64>, Option<f64>) {
+        let view = self.config.as_ref().and_then(|config| config.view.as_ref());
+        (
+            resolve_size(&self.width, view.and_then(|view| view.continuous_width)),
+            resolve_size(&self.height, view.and_then(|view| view.continuous_height)),
+        )
+    }
+}
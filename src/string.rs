@@ -12,7 +12,135 @@
 use crate::schema::*;
 // use std::str::FromStr;
 // use std::convert::TryFrom;
+use serde::ser::{self, Impossible, Serialize, SerializeStruct, Serializer};
 use serde_json;
+use serde_json::Map;
+use std::io;
+
+// a writer counting the bytes written, to measure the json without keeping it
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// a serializer for a chart skipping its `$schema`, without building a copy of the json
+struct WithoutSchema<S>(S);
+
+// a struct being serialized by `WithoutSchema`
+struct WithoutSchemaStruct<S>(S);
+
+fn not_a_chart<E: ser::Error>() -> E {
+    E::custom("only a chart can be serialized without its `$schema`")
+}
+
+// for the methods of `WithoutSchema` serializing anything but a struct
+macro_rules! not_a_chart {
+    ( $( $method:ident ( $( $t:ty ),* ) -> $ok:ty ; )* ) => {
+        $(
+            fn $method(self, $( _: $t ),*) -> Result<$ok, S::Error> {
+                Err(not_a_chart())
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for WithoutSchema<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+    type SerializeMap = Impossible<S::Ok, S::Error>;
+    type SerializeStruct = WithoutSchemaStruct<S::SerializeStruct>;
+    type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        Ok(WithoutSchemaStruct(self.0.serialize_struct(name, len)?))
+    }
+
+    not_a_chart!(
+        serialize_bool(bool) -> S::Ok;
+        serialize_i8(i8) -> S::Ok;
+        serialize_i16(i16) -> S::Ok;
+        serialize_i32(i32) -> S::Ok;
+        serialize_i64(i64) -> S::Ok;
+        serialize_u8(u8) -> S::Ok;
+        serialize_u16(u16) -> S::Ok;
+        serialize_u32(u32) -> S::Ok;
+        serialize_u64(u64) -> S::Ok;
+        serialize_f32(f32) -> S::Ok;
+        serialize_f64(f64) -> S::Ok;
+        serialize_char(char) -> S::Ok;
+        serialize_str(&str) -> S::Ok;
+        serialize_bytes(&[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(&'static str) -> S::Ok;
+        serialize_unit_variant(&'static str, u32, &'static str) -> S::Ok;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    );
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<S::Ok, S::Error> {
+        Err(not_a_chart())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<S::Ok, S::Error> {
+        Err(not_a_chart())
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<S::Ok, S::Error> {
+        Err(not_a_chart())
+    }
+}
+
+impl<S: SerializeStruct> SerializeStruct for WithoutSchemaStruct<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        if key == "$schema" {
+            self.0.skip_field(key)
+        } else {
+            self.0.serialize_field(key, value)
+        }
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
 
 // impl FromStr for Vegalite {
 //     type Err = serde_json::Error;
@@ -22,11 +150,71 @@ use serde_json;
 //     }
 // }
 
+// sort the keys of every object, and write whole floats as integers so that `1.0` and `1` render the same
+fn normalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(String, serde_json::Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, normalize(v)))
+                    .collect::<Map<String, serde_json::Value>>(),
+            )
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(normalize).collect())
+        }
+        serde_json::Value::Number(n) => match n.as_f64() {
+            // integers up to 2^53 are exact in a f64
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+                serde_json::Value::from(f as i64)
+            }
+            _ => serde_json::Value::Number(n),
+        },
+        value => value,
+    }
+}
+
 impl Vegalite {
-    /// Render the json for a graph
+    /// Render the json for a graph. The json is minimal: on a single line, without whitespace, and without the
+    /// fields that are not set.
     pub fn to_string(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    /// Render the json for a graph in a stable form for snapshot tests (with `insta` for example): pretty-printed,
+    /// with the keys of every object sorted and whole floats written as integers. Two charts equal as json always
+    /// give the same string, whatever the order their fields were set in.
+    pub fn to_snapshot_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&normalize(serde_json::to_value(self)?))
+    }
+
+    /// The size in bytes of the json rendered by [`to_string`](#method.to_string), computed without allocating
+    /// the json. Useful to monitor the size of the specs sent by an API.
+    pub fn serialized_size(&self) -> Result<usize, serde_json::Error> {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, self)?;
+        Ok(counter.0)
+    }
+
+    /// Render the json for a graph without its `$schema`, to embed it inside another json document.
+    ///
+    /// The graph is left untouched. The output is not a standalone top-level spec: the `$schema` has to be
+    /// provided by the embedding document or added back before rendering it on its own.
+    pub fn to_json_bare(&self) -> Result<String, serde_json::Error> {
+        let mut json = Vec::new();
+        self.serialize(WithoutSchema(&mut serde_json::Serializer::new(&mut json)))?;
+        Ok(String::from_utf8(json).expect("serde_json writes utf-8"))
+    }
+
+    /// Set the description of the chart, a text alternative for screen readers when the chart is embedded in a
+    /// page. Also available on the builder with `VegaliteBuilder::description`.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
 }
 
 // impl TryFrom<&Vegalite> for String {
@@ -167,3 +355,68 @@ from_into_array_of_str!(
 //         //assert_eq!(vega1, vega2);
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn serialized_size_is_the_length_of_the_json() {
+        let chart = Vegalite {
+            title: Some("Größe".into()),
+            data: UrlData::from_json_value(json!([{"a": "é", "b": 1.5}]))
+                .unwrap()
+                .into(),
+            mark: Some(Mark::Bar.into()),
+            ..Default::default()
+        };
+        let json = chart.to_string().unwrap();
+        assert_eq!(chart.serialized_size().unwrap(), json.len());
+    }
+
+    #[test]
+    fn snapshot_string() {
+        let chart = Vegalite {
+            width: Some(HeightUnion::Double(200.0)),
+            columns: Some(2.0),
+            data: UrlData::from_json_value(json!([{"b": 0.5, "a": 1.0}]))
+                .unwrap()
+                .into(),
+            ..Default::default()
+        };
+        // `columns` is serialized after `width`
+        assert!(chart
+            .to_string()
+            .unwrap()
+            .ends_with(r#""width":200.0,"columns":2.0}"#));
+        let expected = r#"{
+  "columns": 2,
+  "data": {
+    "values": [
+      {
+        "a": 1,
+        "b": 0.5
+      }
+    ]
+  },
+  "width": 200
+}"#;
+        assert_eq!(chart.to_snapshot_string().unwrap(), expected);
+    }
+
+    #[test]
+    fn json_bare() {
+        let chart = Vegalite {
+            schema: Some("https://vega.github.io/schema/vega-lite/v4.json".to_string()),
+            title: Some("bare".into()),
+            mark: Some(Mark::Bar.into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            chart.to_json_bare().unwrap(),
+            r#"{"mark":"bar","title":"bare"}"#
+        );
+        assert!(chart.to_string().unwrap().starts_with(r#"{"$schema":"#));
+    }
+}
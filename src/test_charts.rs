@@ -0,0 +1,41 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Charts shared by the tests.
+//
+// A `Vegalite` is large (about 185 KB), and the 2 MiB stack of the test threads overflows in debug builds when
+// it's copied by value a few times, as builder chains do. Tests build their charts in place: a struct literal
+// from `Default::default()`, then fields set through `encoding_mut()` or assignments.
+
+use crate::*;
+
+/// A bar chart of `a` on x and `b` on y, without data
+pub(crate) fn bar_chart() -> Vegalite {
+    let mut chart = Vegalite {
+        mark: Some(Mark::Bar.into()),
+        ..Default::default()
+    };
+    chart.encoding_mut().x = Some(XClassBuilder::default().field("a").build().unwrap());
+    chart.encoding_mut().y = Some(YClassBuilder::default().field("b").build().unwrap());
+    chart
+}
+
+/// Run `f` on a thread with the stack size of a main thread, for the tests deserializing a `Config`, that alone
+/// takes about 1 MB of stack in debug builds.
+pub(crate) fn on_large_stack(f: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
@@ -0,0 +1,144 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::export::DEFAULT_SCHEMA;
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+
+fn fields_to_text(fields: &[&str]) -> PurpleText {
+    match fields {
+        [field] => PurpleText::String(field.to_string()),
+        fields => PurpleText::StringArray(fields.iter().map(|f| f.to_string()).collect()),
+    }
+}
+
+fn lookup_fields(fields: &[&str]) -> Option<Vec<String>> {
+    if fields.is_empty() {
+        None
+    } else {
+        Some(fields.iter().map(|f| f.to_string()).collect())
+    }
+}
+
+impl Lookup {
+    /// Look up the rows of `data` whose `key` field matches, keeping their `fields` (all of them if empty).
+    pub fn from_data(data: Data, key: &str, fields: &[&str]) -> Self {
+        Lookup {
+            data: Some(data),
+            key: Some(key.to_string()),
+            fields: lookup_fields(fields),
+            ..Default::default()
+        }
+    }
+
+    /// Look up the values selected in the selection `name`, keeping their `fields` (all of them if empty). This
+    /// allows a view to show details of the items selected in another view.
+    pub fn from_selection(name: &str, fields: &[&str]) -> Self {
+        Lookup {
+            selection: Some(name.to_string()),
+            fields: lookup_fields(fields),
+            ..Default::default()
+        }
+    }
+}
+
+impl Transform {
+    /// A `lookup` transform, enriching each row with the values found in `from` (a secondary data source or a
+    /// selection, see [`Lookup::from_data`](struct.Lookup.html#method.from_data) and
+    /// [`Lookup::from_selection`](struct.Lookup.html#method.from_selection)) for the value of its `field`.
+    pub fn lookup(field: &str, from: Lookup) -> Self {
+        Transform {
+            lookup: Some(field.to_string()),
+            from: Some(from),
+            ..Default::default()
+        }
+    }
+
+    /// A `bin` transform, discretizing `field` into the bin start (and end) fields named in `as_fields`. `params`
+    /// is either `true` to use the default binning, or a `BinParams`.
+    pub fn bin(field: &str, as_fields: &[&str], params: impl Into<PurpleBin>) -> Self {
+        Transform {
+            bin: Some(params.into()),
+            field: Some(field.to_string()),
+            transform_as: Some(fields_to_text(as_fields)),
+            ..Default::default()
+        }
+    }
+
+    /// A `sample` transform, keeping a random sample of at most `n` rows of the data.
+    pub fn sample(n: usize) -> Self {
+        Transform {
+            sample: Some(n as f64),
+            ..Default::default()
+        }
+    }
+
+    /// A `fold` transform, turning the columns `fields` into rows of key-value pairs, written to the two fields of
+    /// `as_fields` (`key` and `value` in Vega-Lite if not set).
+    pub fn fold(fields: &[&str], as_fields: [&str; 2]) -> Self {
+        Transform {
+            fold: Some(fields.iter().map(|f| f.to_string()).collect()),
+            transform_as: Some(fields_to_text(&as_fields)),
+            ..Default::default()
+        }
+    }
+
+    /// A `stack` transform, stacking `field` within each group of `groupby` fields and writing the start and end
+    /// of each stacked value to the two fields of `as_fields`. `offset` defaults to `zero`, use `center` for
+    /// streamgraphs and `normalize` to stack to 100%. `sort` orders the values within each stack.
+    pub fn stack(
+        field: &str,
+        groupby: &[&str],
+        as_fields: [&str; 2],
+        offset: Option<StackOffset>,
+        sort: Option<Vec<SortField>>,
+    ) -> Self {
+        Transform {
+            stack: Some(field.to_string()),
+            groupby: Some(groupby.iter().map(|f| f.to_string()).collect()),
+            transform_as: Some(fields_to_text(&as_fields)),
+            offset,
+            sort,
+            ..Default::default()
+        }
+    }
+}
+
+impl Vegalite {
+    /// A chart of several columns of `data` against the same `x`, for example a temperature and a precipitation
+    /// over time. The `measures` columns are folded into `measure` and `value` fields, plotted on `y` with one
+    /// color per measure.
+    pub fn multi_measure(data: UrlData, x: XClass, measures: &[&str], mark: Mark) -> Vegalite {
+        Vegalite {
+            schema: Some(DEFAULT_SCHEMA.to_string()),
+            data: RemovableValue::Specified(data),
+            transform: Some(vec![Transform::fold(measures, ["measure", "value"])]),
+            mark: Some(mark.into()),
+            encoding: Some(Encoding {
+                x: Some(x),
+                y: Some(YClass {
+                    field: Some("value".into()),
+                    def_type: Some(StandardType::Quantitative),
+                    ..Default::default()
+                }),
+                color: Some(DefWithConditionMarkPropFieldDefGradientStringNull {
+                    field: Some("measure".into()),
+                    def_with_condition_mark_prop_field_def_gradient_string_null_type: Some(
+                        StandardType::Nominal,
+                    ),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
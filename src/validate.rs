@@ -0,0 +1,533 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::schema::*;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fmt;
+
+/// An issue found in a chart by [`Vegalite::validate`](struct.Vegalite.html#method.validate)
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    /// Where the issue is in the json of the chart, for example `layer[1].encoding.y`
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+// check a channel definition
+fn check_channel(def: &Map<String, Value>, path: &str, issues: &mut Vec<ValidationIssue>) {
+    if let Some(aggregate) = def.get("aggregate") {
+        if aggregate.as_str() != Some("count") && !def.contains_key("field") {
+            issues.push(ValidationIssue {
+                path: path.to_string(),
+                message: format!("aggregate `{}` requires a `field`", aggregate),
+            });
+        }
+    }
+}
+
+// secondary channels, with the primary channel they need
+const SECONDARY_CHANNELS: &[(&str, &str)] = &[
+    ("x2", "x"),
+    ("y2", "y"),
+    ("xError", "x"),
+    ("xError2", "xError"),
+    ("yError", "y"),
+    ("yError2", "yError"),
+    ("latitude2", "latitude"),
+    ("longitude2", "longitude"),
+];
+
+// `channels` are the channels set in the view, including the ones inherited from the parent of a layer
+fn check_encoding(
+    encoding: &Map<String, Value>,
+    channels: &HashSet<&str>,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for (channel, def) in encoding {
+        let path = join_path(path, channel);
+        match def {
+            Value::Object(def) => check_channel(def, &path, issues),
+            // `detail` and `tooltip` can be a list of definitions
+            Value::Array(defs) => {
+                for (i, def) in defs.iter().enumerate() {
+                    if let Value::Object(def) = def {
+                        check_channel(def, &format!("{}[{}]", path, i), issues);
+                    }
+                }
+            }
+            _ => (),
+        }
+        for (secondary, primary) in SECONDARY_CHANNELS {
+            if channel == secondary && !channels.contains(primary) {
+                issues.push(ValidationIssue {
+                    path: path.clone(),
+                    message: format!("`{}` requires `{}` to be set", secondary, primary),
+                });
+            }
+        }
+    }
+}
+
+// look for encodings in every view of the chart, `inherited` are the channels set by the parent of a layer
+fn visit<'a>(
+    value: &'a Value,
+    path: &str,
+    inherited: &HashSet<&'a str>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match value {
+        Value::Object(map) => {
+            let mut channels = inherited.clone();
+            if let Some(Value::Object(encoding)) = map.get("encoding") {
+                channels.extend(encoding.keys().map(String::as_str));
+            }
+            for (key, value) in map {
+                let path = join_path(path, key);
+                match (key.as_str(), value) {
+                    ("encoding", Value::Object(encoding)) => {
+                        check_encoding(encoding, &channels, &path, issues)
+                    }
+                    // layers share the encoding of their parent
+                    ("layer", _) => visit(value, &path, &channels, issues),
+                    // no views to check in those
+                    ("data", _) | ("datasets", _) | ("config", _) | ("usermeta", _) => (),
+                    _ => visit(value, &path, &HashSet::new(), issues),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                visit(value, &format!("{}[{}]", path, i), inherited, issues);
+            }
+        }
+        _ => (),
+    }
+}
+
+// check that parentheses, brackets and braces are balanced in an expression, outside of string literals
+fn check_delimiters(expr: &str) -> Option<String> {
+    let mut open = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    for c in expr.chars() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' | '[' | '{' => open.push(c),
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if open.pop() != Some(expected) {
+                    return Some(format!("unexpected `{}` in expression `{}`", c, expr));
+                }
+            }
+            _ => (),
+        }
+    }
+    if quote.is_some() {
+        Some(format!("unterminated string in expression `{}`", expr))
+    } else {
+        open.pop()
+            .map(|c| format!("unclosed `{}` in expression `{}`", c, expr))
+    }
+}
+
+// the fields accessed as `datum.field` in an expression, outside of string literals
+fn datum_fields(expr: &str) -> Vec<String> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let chars: Vec<char> = expr.chars().collect();
+    let mut fields = Vec::new();
+    let mut quote = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == '\\' {
+                i += 1;
+            } else if c == q {
+                quote = None;
+            }
+        } else if c == '\'' || c == '"' {
+            quote = Some(c);
+        } else if chars[i..].starts_with(&['d', 'a', 't', 'u', 'm', '.'])
+            && (i == 0 || !is_ident(chars[i - 1]))
+        {
+            i += 6;
+            let start = i;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            fields.push(chars[start..i].iter().collect());
+            continue;
+        }
+        i += 1;
+    }
+    fields
+}
+
+// transforms creating fields with default names when they have no `as`
+const DEFAULT_AS: &[(&str, &[&str])] = &[
+    ("density", &["value", "density"]),
+    ("quantile", &["prob", "value"]),
+];
+
+// the fields of the chart, when every data source is inline: the fields of the inline values, and the fields
+// created by transforms (the `as` of transforms, or their default names). Returns if there is inline data, `None`
+// when the fields can't be known.
+fn known_fields(value: &Value, fields: &mut HashSet<String>) -> Option<bool> {
+    let mut inline = false;
+    match value {
+        Value::Object(map) => {
+            match map.get("as") {
+                // a bin transform with a single `as` also creates `<as>_end`
+                Some(Value::String(name)) if map.contains_key("bin") => {
+                    fields.insert(format!("{}_end", name));
+                }
+                Some(_) => (),
+                None => {
+                    for (transform, names) in DEFAULT_AS {
+                        if map.contains_key(*transform) {
+                            fields.extend(names.iter().map(|name| name.to_string()));
+                        }
+                    }
+                }
+            }
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("data", Value::Object(data)) => match data.get("values") {
+                        Some(Value::Array(rows)) => {
+                            inline = true;
+                            for row in rows {
+                                if let Value::Object(row) = row {
+                                    fields.extend(row.keys().cloned());
+                                } else {
+                                    // primitive values are ingested as a `data` field
+                                    fields.insert("data".to_string());
+                                }
+                            }
+                        }
+                        _ => return None,
+                    },
+                    // the fields created by those depend on the data
+                    ("pivot", _) | ("lookup", _) => return None,
+                    ("fold", _) => {
+                        fields.insert("key".to_string());
+                        fields.insert("value".to_string());
+                    }
+                    ("as", Value::String(name)) => {
+                        fields.insert(name.clone());
+                    }
+                    ("as", Value::Array(names)) => {
+                        fields.extend(names.iter().filter_map(|n| n.as_str().map(String::from)));
+                    }
+                    ("datasets", _) | ("config", _) | ("usermeta", _) => (),
+                    _ => inline |= known_fields(value, fields)?,
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                inline |= known_fields(value, fields)?;
+            }
+        }
+        _ => (),
+    }
+    Some(inline)
+}
+
+// check every expression of the chart
+fn visit_expressions(
+    value: &Value,
+    path: &str,
+    fields: &Option<HashSet<String>>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = join_path(path, key);
+                match (key.as_str(), value) {
+                    ("calculate", Value::String(expr))
+                    | ("filter", Value::String(expr))
+                    | ("test", Value::String(expr))
+                    | ("labelExpr", Value::String(expr)) => {
+                        if let Some(message) = check_delimiters(expr) {
+                            issues.push(ValidationIssue {
+                                path: path.clone(),
+                                message,
+                            });
+                        }
+                        // in a `labelExpr`, `datum` is the axis or legend label, not a data row
+                        if let (Some(fields), false) = (fields, key == "labelExpr") {
+                            for field in datum_fields(expr) {
+                                if !fields.contains(&field) {
+                                    issues.push(ValidationIssue {
+                                        path: path.clone(),
+                                        message: format!(
+                                            "field `{}` is not in the inline data of the chart",
+                                            field
+                                        ),
+                                    });
+                                }
+                            }
+                        }
+                    }
+                    ("data", _) | ("datasets", _) | ("usermeta", _) => (),
+                    _ => visit_expressions(value, &path, fields, issues),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                visit_expressions(value, &format!("{}[{}]", path, i), fields, issues);
+            }
+        }
+        _ => (),
+    }
+}
+
+impl Vegalite {
+    /// Check the chart for mistakes that Vega-Lite doesn't always report clearly:
+    /// * an encoding channel with an `aggregate` other than `count` must have a `field`,
+    /// * a secondary channel (`x2`, `y2`, `xError`, `latitude2`, ...) requires its primary channel (`x`, `y`,
+    ///   `latitude`, ...), in the same view or in the parent of a layer.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let json = serde_json::to_value(self).map_err(|e| {
+            vec![ValidationIssue {
+                path: String::new(),
+                message: format!("chart can't be serialized: {}", e),
+            }]
+        })?;
+        let mut issues = Vec::new();
+        visit(&json, "", &HashSet::new(), &mut issues);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Panic with the issues found by [`validate`](#method.validate), one per line, if the chart isn't valid. Meant
+    /// for tests, as a shorter `chart.validate().unwrap()` with readable issues.
+    #[track_caller]
+    pub fn assert_valid(&self) {
+        if let Err(issues) = self.validate() {
+            let issues: Vec<String> = issues.iter().map(|issue| format!("  {}", issue)).collect();
+            panic!("chart is not valid:\n{}", issues.join("\n"));
+        }
+    }
+
+    /// Check the expressions of the chart (`calculate` and `filter` transforms, condition `test`s and
+    /// `labelExpr`s) for obvious mistakes, as they fail silently in the browser:
+    /// * parentheses, brackets and braces must be balanced, and strings terminated,
+    /// * fields accessed as `datum.field` in transforms and conditions must exist, when all the data of the chart
+    ///   is inline.
+    ///
+    /// This doesn't parse the expressions, so the check is best-effort: some mistakes go unnoticed, and fields
+    /// are only checked when they can be known from the inline data and the fields created by the transforms.
+    pub fn validate_expressions(&self) -> Result<(), Vec<ValidationIssue>> {
+        let json = serde_json::to_value(self).map_err(|e| {
+            vec![ValidationIssue {
+                path: String::new(),
+                message: format!("chart can't be serialized: {}", e),
+            }]
+        })?;
+        let mut fields = HashSet::new();
+        let fields = match known_fields(&json, &mut fields) {
+            Some(true) => Some(fields),
+            _ => None,
+        };
+        let mut issues = Vec::new();
+        visit_expressions(&json, "", &fields, &mut issues);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_charts::bar_chart;
+    use serde_json::json;
+
+    fn messages(chart: &Vegalite) -> Vec<String> {
+        match chart.validate() {
+            Ok(()) => Vec::new(),
+            Err(issues) => issues.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn x2() -> Option<X2Class> {
+        Some(X2Class {
+            field: Some("c".into()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn secondary_channel_without_primary() {
+        let mut chart = bar_chart();
+        chart.encoding_mut().x = None;
+        chart.encoding_mut().x2 = x2();
+        assert_eq!(
+            messages(&chart),
+            vec!["encoding.x2: `x2` requires `x` to be set"]
+        );
+    }
+
+    #[test]
+    fn secondary_channel_with_primary() {
+        let mut chart = bar_chart();
+        chart.encoding_mut().x2 = x2();
+        assert_eq!(messages(&chart), Vec::<String>::new());
+    }
+
+    #[test]
+    fn secondary_channel_with_primary_in_parent() {
+        let mut layer = LayerSpec {
+            mark: Some(Mark::Rule.into()),
+            ..Default::default()
+        };
+        layer.encoding_mut().x2 = x2();
+        let mut chart = bar_chart();
+        chart.mark = None;
+        chart.layer = Some(vec![layer]);
+        assert_eq!(messages(&chart), Vec::<String>::new());
+    }
+
+    #[test]
+    fn aggregate_requires_a_field() {
+        let mut chart = bar_chart();
+        chart.encoding_mut().y = Some(YClass {
+            aggregate: Some(NonArgAggregateOp::Mean.into()),
+            ..Default::default()
+        });
+        assert_eq!(
+            messages(&chart),
+            vec![r#"encoding.y: aggregate `"mean"` requires a `field`"#]
+        );
+    }
+
+    #[test]
+    fn count_without_field() {
+        let mut chart = bar_chart();
+        chart.encoding_mut().y = Some(YClass {
+            aggregate: Some(NonArgAggregateOp::Count.into()),
+            ..Default::default()
+        });
+        assert_eq!(messages(&chart), Vec::<String>::new());
+    }
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(check_delimiters("(datum.a + [1, 2][0]) > {a: 3}.a"), None);
+        assert_eq!(check_delimiters("')' + \"[\" + (datum.a)"), None);
+        assert_eq!(check_delimiters(r"'it\'s' + datum.a"), None);
+        assert_eq!(
+            check_delimiters("(datum.a"),
+            Some("unclosed `(` in expression `(datum.a`".to_string())
+        );
+        assert_eq!(
+            check_delimiters("[datum.a)"),
+            Some("unexpected `)` in expression `[datum.a)`".to_string())
+        );
+        assert_eq!(
+            check_delimiters("'datum.a"),
+            Some("unterminated string in expression `'datum.a`".to_string())
+        );
+    }
+
+    #[test]
+    fn fields_of_expressions() {
+        assert_eq!(datum_fields("datum.a + datum.b_c > 0"), vec!["a", "b_c"]);
+        assert_eq!(datum_fields("datum.a == 'datum.b'"), vec!["a"]);
+        assert_eq!(
+            datum_fields(r#"mydatum.a + "it\"s datum.b""#),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn fields_of_transforms() {
+        let chart = json!({
+            "data": {"values": [{"a": 1}, {"b": 2}]},
+            "transform": [
+                {"bin": true, "field": "a", "as": "a_bin"},
+                {"bin": true, "field": "b", "as": ["b_start", "b_stop"]},
+                {"density": "a"},
+                {"quantile": "b"},
+                {"calculate": "datum.a * 2", "as": "c"},
+                {"fold": ["a", "b"]},
+            ]
+        });
+        let mut fields = HashSet::new();
+        assert_eq!(known_fields(&chart, &mut fields), Some(true));
+        let mut fields: Vec<_> = fields.into_iter().collect();
+        fields.sort();
+        let expected = vec![
+            "a",
+            "a_bin",
+            "a_bin_end",
+            "b",
+            "b_start",
+            "b_stop",
+            "c",
+            "density",
+            "key",
+            "prob",
+            "value",
+        ];
+        assert_eq!(fields, expected);
+    }
+
+    #[test]
+    fn fields_of_data_from_url_are_unknown() {
+        let chart = json!({"data": {"url": "data.csv"}, "transform": [{"filter": "datum.a > 1"}]});
+        assert_eq!(known_fields(&chart, &mut HashSet::new()), None);
+        let chart = json!({
+            "data": {"values": [{"a": 1}]},
+            "transform": [{"pivot": "a", "value": "a"}]
+        });
+        assert_eq!(known_fields(&chart, &mut HashSet::new()), None);
+    }
+}
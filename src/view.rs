@@ -0,0 +1,130 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::removable_value::RemovableValue;
+use crate::schema::*;
+use std::collections::HashMap;
+
+/// Mutable access to one of the views of a chart, whatever its nesting
+pub(crate) enum ViewMut<'a> {
+    /// The chart itself
+    Top(&'a mut Vegalite),
+    /// A faceted or repeated spec
+    Spec(&'a mut SpecClass),
+    /// A concatenated spec
+    Concat(&'a mut Spec),
+    /// A layer
+    Layer(&'a mut LayerSpec),
+}
+
+// accessors for fields with the same type in every kind of view
+macro_rules! view_field {
+    ( $( $field:ident : $t:ty ),* $(,)? ) => {
+        impl ViewMut<'_> {
+            $(
+                pub(crate) fn $field(&mut self) -> &mut $t {
+                    match self {
+                        ViewMut::Top(v) => &mut v.$field,
+                        ViewMut::Spec(v) => &mut v.$field,
+                        ViewMut::Concat(v) => &mut v.$field,
+                        ViewMut::Layer(v) => &mut v.$field,
+                    }
+                }
+            )*
+        }
+    };
+}
+
+view_field!(
+    data: RemovableValue<UrlData>,
+    selection: Option<HashMap<String, SelectionDef>>,
+    transform: Option<Vec<Transform>>,
+);
+
+/// Mutable access to the encoding of a view, that has a different type in layers
+pub(crate) enum EncodingMut<'a> {
+    /// The encoding of a chart, faceted or repeated spec, or concatenated spec
+    Unit(&'a mut Encoding),
+    /// The encoding of a layer
+    Layer(&'a mut LayerEncoding),
+}
+
+impl ViewMut<'_> {
+    /// The encoding of the view, if any
+    pub(crate) fn encoding(&mut self) -> Option<EncodingMut<'_>> {
+        match self {
+            ViewMut::Top(v) => v.encoding.as_mut().map(EncodingMut::Unit),
+            ViewMut::Spec(v) => v.encoding.as_mut().map(EncodingMut::Unit),
+            ViewMut::Concat(v) => v.encoding.as_mut().map(EncodingMut::Unit),
+            ViewMut::Layer(v) => v.encoding.as_mut().map(EncodingMut::Layer),
+        }
+    }
+}
+
+fn visit_layers<S>(
+    layers: &mut Option<Vec<LayerSpec>>,
+    state: &S,
+    f: &mut dyn FnMut(ViewMut, &S) -> S,
+) {
+    for layer in layers.iter_mut().flatten() {
+        let state = f(ViewMut::Layer(layer), state);
+        visit_layers(&mut layer.layer, &state, f);
+    }
+}
+
+fn visit_concat<S>(specs: &mut Option<Vec<Spec>>, state: &S, f: &mut dyn FnMut(ViewMut, &S) -> S) {
+    for spec in specs.iter_mut().flatten() {
+        let state = f(ViewMut::Concat(spec), state);
+        visit_layers(&mut spec.layer, &state, f);
+        if let Some(inner) = spec.spec.as_mut() {
+            visit_spec(inner, &state, f);
+        }
+        visit_concat(&mut spec.concat, &state, f);
+        visit_concat(&mut spec.hconcat, &state, f);
+        visit_concat(&mut spec.vconcat, &state, f);
+    }
+}
+
+fn visit_spec<S>(spec: &mut SpecClass, state: &S, f: &mut dyn FnMut(ViewMut, &S) -> S) {
+    let state = f(ViewMut::Spec(spec), state);
+    visit_layers(&mut spec.layer, &state, f);
+    if let Some(inner) = spec.spec.as_mut() {
+        visit_spec(inner, &state, f);
+    }
+    visit_concat(&mut spec.concat, &state, f);
+    visit_concat(&mut spec.hconcat, &state, f);
+    visit_concat(&mut spec.vconcat, &state, f);
+}
+
+impl Vegalite {
+    /// Call `f` on every view of the chart: the chart itself, then its layers, faceted or repeated spec and
+    /// concatenated specs, recursively.
+    pub(crate) fn visit_views_mut(&mut self, f: &mut dyn FnMut(ViewMut)) {
+        self.visit_views_with_state_mut(&(), &mut |view, _| f(view));
+    }
+
+    /// Call `f` on every view of the chart, like [`visit_views_mut`](#method.visit_views_mut). `f` gets the state
+    /// returned for the parent of the view (`root` for the chart itself), and returns the state of the view.
+    pub(crate) fn visit_views_with_state_mut<S>(
+        &mut self,
+        root: &S,
+        f: &mut dyn FnMut(ViewMut, &S) -> S,
+    ) {
+        let state = f(ViewMut::Top(self), root);
+        visit_layers(&mut self.layer, &state, f);
+        if let Some(spec) = self.spec.as_mut() {
+            visit_spec(spec, &state, f);
+        }
+        visit_concat(&mut self.concat, &state, f);
+        visit_concat(&mut self.hconcat, &state, f);
+        visit_concat(&mut self.vconcat, &state, f);
+    }
+}